@@ -0,0 +1,788 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod ai_agent_registry {
+    use city_common::access::{AccessControl, AccessControlled, AccessError, RoleId, AGENT_MANAGER, PAUSER};
+    use city_common::agents::AgentStats;
+    use city_common::pausable::{FunctionId, PauseError, PauseState, Pausable};
+    use city_common::ZoneId;
+    use ink::storage::traits::StorageKey;
+    use ink::storage::{Lazy, Mapping};
+    use ink::prelude::vec::Vec;
+    use ink::prelude::string::String;
+
+    /// Layout version of this contract's storage. Bump it together with a
+    /// step in `migrate` whenever stored types change. Fields added later
+    /// must live outside the root cell (in a `Lazy` or `Mapping`) so the
+    /// root still decodes after `set_code`.
+    pub const STORAGE_VERSION: u32 = 2;
+    /// Zone of agents migrated from storage version 1, whose zones were free
+    /// text. Agent managers reassign them with `set_agent_zone`.
+    pub const UNKNOWN_ZONE: ZoneId = ZoneId::MAX;
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        Unauthorized,
+        AgentNotFound,
+        UpgradeFailed,
+        UnsupportedStorageVersion,
+        MigrationOutOfOrder,
+        Paused,
+        PauseCoolingDown,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl From<PauseError> for Error {
+        fn from(error: PauseError) -> Self {
+            match error {
+                PauseError::Paused => Error::Paused,
+                PauseError::Unauthorized => Error::Unauthorized,
+                PauseError::CoolingDown => Error::PauseCoolingDown,
+            }
+        }
+    }
+
+    impl From<AccessError> for Error {
+        fn from(_: AccessError) -> Self {
+            Error::Unauthorized
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum AgentStatus {
+        Active,
+        Learning,
+        Maintenance,
+        Offline,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AIAgent {
+        pub id: u32,
+        pub name: String,
+        pub zone: ZoneId,
+        pub status: AgentStatus,
+        pub performance_score: u32,
+        pub decisions_made: u32,
+        pub energy_saved: u128,
+        pub cost_reduction: u128,
+        pub last_update: u64,
+        pub specialization: String,
+        pub confidence_level: u32,
+    }
+
+    /// `AIAgent` as stored before storage version 2, with a free-text zone.
+    #[derive(scale::Encode, scale::Decode)]
+    struct AIAgentV1 {
+        id: u32,
+        name: String,
+        zone: String,
+        status: AgentStatus,
+        performance_score: u32,
+        decisions_made: u32,
+        energy_saved: u128,
+        cost_reduction: u128,
+        last_update: u64,
+        specialization: String,
+        confidence_level: u32,
+    }
+
+    impl From<AIAgentV1> for AIAgent {
+        fn from(agent: AIAgentV1) -> Self {
+            Self {
+                id: agent.id,
+                name: agent.name,
+                zone: UNKNOWN_ZONE,
+                status: agent.status,
+                performance_score: agent.performance_score,
+                decisions_made: agent.decisions_made,
+                energy_saved: agent.energy_saved,
+                cost_reduction: agent.cost_reduction,
+                last_update: agent.last_update,
+                specialization: agent.specialization,
+                confidence_level: agent.confidence_level,
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AgentDecision {
+        pub agent_id: u32,
+        pub decision_type: String,
+        pub parameters: String,
+        pub impact_score: u32,
+        pub timestamp: u64,
+        pub validation_score: u32,
+    }
+
+    #[ink(storage)]
+    pub struct AIAgentRegistry {
+        agents: Mapping<u32, AIAgent>,
+        decisions: Mapping<u32, Vec<AgentDecision>>,
+        agent_count: u32,
+        total_energy_saved: u128,
+        total_cost_reduction: u128,
+        zone_energy_saved: Mapping<ZoneId, u128>,
+        access: AccessControl,
+        pauses: PauseState,
+        governance_contract: AccountId,
+        storage_version: Lazy<u32>,
+        /// Next agent `migrate` converts; `None` while no migration runs.
+        migration_cursor: Lazy<Option<u32>>,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        function: FunctionId,
+        account: AccountId,
+        until: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        function: FunctionId,
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        code_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct StorageMigrated {
+        from: u32,
+        to: u32,
+    }
+
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        pending_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AgentRegistered {
+        #[ink(topic)]
+        agent_id: u32,
+        name: String,
+        zone: ZoneId,
+    }
+
+    #[ink(event)]
+    pub struct DecisionMade {
+        #[ink(topic)]
+        agent_id: u32,
+        decision_type: String,
+        impact_score: u32,
+    }
+
+    #[ink(event)]
+    pub struct PerformanceUpdated {
+        #[ink(topic)]
+        agent_id: u32,
+        performance_score: u32,
+        energy_saved: u128,
+    }
+
+    impl AIAgentRegistry {
+        #[ink(constructor)]
+        pub fn new(governance_contract: AccountId) -> Self {
+            let caller = Self::env().caller();
+            let mut access = AccessControl::new(caller);
+            access.set_role(AGENT_MANAGER, caller);
+            access.set_role(PAUSER, caller);
+            let mut storage_version = Lazy::new();
+            storage_version.set(&STORAGE_VERSION);
+            Self {
+                agents: Mapping::default(),
+                decisions: Mapping::default(),
+                agent_count: 0,
+                total_energy_saved: 0,
+                total_cost_reduction: 0,
+                zone_energy_saved: Mapping::default(),
+                access,
+                pauses: PauseState::default(),
+                governance_contract,
+                storage_version,
+                migration_cursor: Lazy::new(),
+            }
+        }
+
+        #[ink(message)]
+        pub fn register_agent(
+            &mut self,
+            name: String,
+            zone: ZoneId,
+            specialization: String,
+        ) -> Result<u32> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("register_agent"))?;
+            self.access.ensure_role(AGENT_MANAGER, self.env().caller())?;
+
+            let agent_id = self.agent_count;
+            let agent = AIAgent {
+                id: agent_id,
+                name: name.clone(),
+                zone,
+                status: AgentStatus::Learning,
+                performance_score: 50, // Starting score
+                decisions_made: 0,
+                energy_saved: 0,
+                cost_reduction: 0,
+                last_update: self.env().block_timestamp(),
+                specialization,
+                confidence_level: 75,
+            };
+
+            self.agents.insert(agent_id, &agent);
+            self.agent_count += 1;
+
+            self.env().emit_event(AgentRegistered {
+                agent_id,
+                name,
+                zone,
+            });
+
+            Ok(agent_id)
+        }
+
+        #[ink(message)]
+        pub fn record_decision(
+            &mut self,
+            agent_id: u32,
+            decision_type: String,
+            parameters: String,
+            impact_score: u32,
+        ) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("record_decision"))?;
+            let mut agent = self.agents.get(agent_id).ok_or(Error::AgentNotFound)?;
+            
+            let decision = AgentDecision {
+                agent_id,
+                decision_type: decision_type.clone(),
+                parameters,
+                impact_score,
+                timestamp: self.env().block_timestamp(),
+                validation_score: 0, // Will be updated later by validation
+            };
+
+            // Update agent stats
+            agent.decisions_made += 1;
+            agent.last_update = self.env().block_timestamp();
+            agent.status = AgentStatus::Active;
+            
+            // Update performance based on impact
+            let performance_delta = if impact_score > 80 { 5 } else if impact_score > 60 { 2 } else { 0 };
+            agent.performance_score = (agent.performance_score + performance_delta).min(100);
+
+            self.agents.insert(agent_id, &agent);
+
+            // Store decision
+            let mut agent_decisions = self.decisions.get(agent_id).unwrap_or_default();
+            agent_decisions.push(decision);
+            self.decisions.insert(agent_id, &agent_decisions);
+
+            self.env().emit_event(DecisionMade {
+                agent_id,
+                decision_type,
+                impact_score,
+            });
+
+            Ok(())
+        }
+
+        /// Adds reported savings to an agent. Zone savings feed the zone
+        /// treasury's performance bonuses, so only agent managers report them.
+        #[ink(message)]
+        pub fn update_performance(
+            &mut self,
+            agent_id: u32,
+            energy_saved: u128,
+            cost_reduction: u128,
+        ) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("update_performance"))?;
+            self.access.ensure_role(AGENT_MANAGER, self.env().caller())?;
+            let mut agent = self.agents.get(agent_id).ok_or(Error::AgentNotFound)?;
+            
+            agent.energy_saved += energy_saved;
+            agent.cost_reduction += cost_reduction;
+            
+            // Update global stats
+            self.total_energy_saved += energy_saved;
+            self.total_cost_reduction += cost_reduction;
+            let zone_saved = self.zone_energy_saved.get(agent.zone).unwrap_or(0);
+            self.zone_energy_saved.insert(agent.zone, &(zone_saved + energy_saved));
+
+            // Calculate new performance score based on impact
+            let impact_factor = (energy_saved / 1000 + cost_reduction / 10000) as u32;
+            agent.performance_score = (agent.performance_score + impact_factor).min(100);
+
+            self.agents.insert(agent_id, &agent);
+
+            self.env().emit_event(PerformanceUpdated {
+                agent_id,
+                performance_score: agent.performance_score,
+                energy_saved: agent.energy_saved,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_agent(&self, agent_id: u32) -> Option<AIAgent> {
+            self.agents.get(agent_id)
+        }
+
+        #[ink(message)]
+        pub fn get_agent_decisions(&self, agent_id: u32) -> Vec<AgentDecision> {
+            self.decisions.get(agent_id).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn get_total_stats(&self) -> (u128, u128, u32) {
+            (self.total_energy_saved, self.total_cost_reduction, self.agent_count)
+        }
+
+        #[ink(message)]
+        pub fn set_agent_status(&mut self, agent_id: u32, status: AgentStatus) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("set_agent_status"))?;
+            self.access.ensure_role(AGENT_MANAGER, self.env().caller())?;
+
+            let mut agent = self.agents.get(agent_id).ok_or(Error::AgentNotFound)?;
+            agent.status = status;
+            agent.last_update = self.env().block_timestamp();
+            self.agents.insert(agent_id, &agent);
+
+            Ok(())
+        }
+
+        /// Moves an agent to another zone. Savings reported earlier stay
+        /// with the zone they were reported for.
+        #[ink(message)]
+        pub fn set_agent_zone(&mut self, agent_id: u32, zone: ZoneId) -> Result<()> {
+            self.access.ensure_role(AGENT_MANAGER, self.env().caller())?;
+
+            let mut agent = self.agents.get(agent_id).ok_or(Error::AgentNotFound)?;
+            agent.zone = zone;
+            agent.last_update = self.env().block_timestamp();
+            self.agents.insert(agent_id, &agent);
+
+            Ok(())
+        }
+
+        /// Replaces this contract's code, keeping its storage. Only the governance
+        /// contract may call it, through an executed proposal.
+        /// Follow it with `migrate` in the same proposal.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<()> {
+            self.ensure_governance()?;
+            self.env().set_code_hash(&code_hash).map_err(|_| Error::UpgradeFailed)?;
+            self.env().emit_event(CodeUpgraded { code_hash });
+            Ok(())
+        }
+
+        /// Brings storage written by an older version up to
+        /// `STORAGE_VERSION`, converting up to `limit` stored agents from
+        /// `offset` on. Batches must run in order, each starting where the
+        /// last one stopped; `get_migration_cursor` tells where that is. The
+        /// version moves on only after the last batch. Governance starts a
+        /// migration through an executed proposal; once started, anyone may
+        /// run the remaining batches, since they only re-encode stored
+        /// entries.
+        #[ink(message)]
+        pub fn migrate(&mut self, offset: u32, limit: u32) -> Result<()> {
+            let cursor = match self.migration_cursor.get().flatten() {
+                Some(cursor) => cursor,
+                None => {
+                    self.ensure_governance()?;
+                    0
+                }
+            };
+            let from = self.storage_version.get().unwrap_or(STORAGE_VERSION);
+            if from > STORAGE_VERSION {
+                return Err(Error::UnsupportedStorageVersion);
+            }
+            if offset != cursor {
+                return Err(Error::MigrationOutOfOrder);
+            }
+            let end = offset.saturating_add(limit).min(self.agent_count);
+            // Steps for later versions go here, oldest first
+            if from < 2 {
+                // Version 2 replaced free-text zones with `ZoneId`s
+                let agents = self.agents.key();
+                for agent_id in offset..end {
+                    let stored = ink::env::get_contract_storage::<_, AIAgentV1>(&(agents, agent_id));
+                    if let Ok(Some(agent)) = stored {
+                        self.agents.insert(agent_id, &AIAgent::from(agent));
+                    }
+                }
+            }
+            if from < STORAGE_VERSION && end < self.agent_count {
+                self.migration_cursor.set(&Some(end));
+                return Ok(());
+            }
+            self.migration_cursor.set(&None);
+            self.storage_version.set(&STORAGE_VERSION);
+            self.env().emit_event(StorageMigrated { from, to: STORAGE_VERSION });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or(STORAGE_VERSION)
+        }
+
+        /// Where the next `migrate` batch starts, while a migration runs.
+        #[ink(message)]
+        pub fn get_migration_cursor(&self) -> Option<u32> {
+            self.migration_cursor.get().flatten()
+        }
+
+        fn ensure_governance(&self) -> Result<()> {
+            if self.env().caller() != self.governance_contract {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+    }
+
+    impl AgentStats for AIAgentRegistry {
+        #[ink(message)]
+        fn zone_energy_saved(&self, zone: ZoneId) -> u128 {
+            self.zone_energy_saved.get(zone).unwrap_or(0)
+        }
+    }
+
+    impl AccessControlled for AIAgentRegistry {
+        #[ink(message)]
+        fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+            self.access.has_role(role, account)
+        }
+
+        #[ink(message)]
+        fn grant_role(&mut self, role: RoleId, account: AccountId) -> core::result::Result<(), AccessError> {
+            let sender = self.env().caller();
+            if self.access.grant_role(sender, role, account)? {
+                self.env().emit_event(RoleGranted { role, account, sender });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn revoke_role(&mut self, role: RoleId, account: AccountId) -> core::result::Result<(), AccessError> {
+            let sender = self.env().caller();
+            if self.access.revoke_role(sender, role, account)? {
+                self.env().emit_event(RoleRevoked { role, account, sender });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn renounce_role(&mut self, role: RoleId) -> core::result::Result<(), AccessError> {
+            let account = self.env().caller();
+            self.access.renounce_role(account, role)?;
+            self.env().emit_event(RoleRevoked { role, account, sender: account });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn owner(&self) -> AccountId {
+            self.access.owner()
+        }
+
+        #[ink(message)]
+        fn pending_owner(&self) -> Option<AccountId> {
+            self.access.pending_owner()
+        }
+
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> core::result::Result<(), AccessError> {
+            let owner = self.env().caller();
+            self.access.transfer_ownership(owner, new_owner)?;
+            self.env().emit_event(OwnershipTransferStarted {
+                owner,
+                pending_owner: new_owner,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn accept_ownership(&mut self) -> core::result::Result<(), AccessError> {
+            let new_owner = self.env().caller();
+            let previous_owner = self.access.accept_ownership(new_owner)?;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            });
+            Ok(())
+        }
+    }
+
+    impl Pausable for AIAgentRegistry {
+        #[ink(message)]
+        fn is_paused(&self, function: FunctionId) -> bool {
+            self.pauses.is_paused(function)
+        }
+
+        #[ink(message)]
+        fn pause(&mut self, function: FunctionId) -> core::result::Result<(), PauseError> {
+            let account = self.env().caller();
+            if !self.access.has_role(PAUSER, account) {
+                return Err(PauseError::Unauthorized);
+            }
+            if self.pauses.pause(function)? {
+                let until = self.pauses.paused_until(function).unwrap_or_default();
+                self.env().emit_event(Paused { function, account, until });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn unpause(&mut self, function: FunctionId) -> core::result::Result<(), PauseError> {
+            self.ensure_governance().map_err(|_| PauseError::Unauthorized)?;
+            if self.pauses.unpause(function) {
+                self.env().emit_event(Unpaused {
+                    function,
+                    account: self.env().caller(),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        type Event = <AIAgentRegistry as ink::reflect::ContractEventBase>::Type;
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        /// Registry deployed by Alice and governed by Charlie.
+        fn registry() -> AIAgentRegistry {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            AIAgentRegistry::new(accounts.charlie)
+        }
+
+        fn register(registry: &mut AIAgentRegistry) -> Result<u32> {
+            registry.register_agent(String::from("Traffic"), 0, String::from("Routing"))
+        }
+
+        fn events() -> Vec<Event> {
+            test::recorded_events()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .collect()
+        }
+
+        #[ink::test]
+        fn the_owner_grants_and_revokes_roles() {
+            let accounts = accounts();
+            let mut registry = registry();
+            assert!(registry.has_role(AGENT_MANAGER, accounts.alice));
+
+            set_caller(accounts.bob);
+            assert_eq!(register(&mut registry), Err(Error::Unauthorized));
+            assert_eq!(registry.grant_role(AGENT_MANAGER, accounts.bob), Err(AccessError::NotOwner));
+
+            set_caller(accounts.alice);
+            assert_eq!(registry.grant_role(AGENT_MANAGER, accounts.bob), Ok(()));
+            set_caller(accounts.bob);
+            assert_eq!(register(&mut registry), Ok(0));
+
+            set_caller(accounts.alice);
+            assert_eq!(registry.revoke_role(AGENT_MANAGER, accounts.bob), Ok(()));
+            // Revoking a role that is not held changes nothing and emits nothing
+            assert_eq!(registry.revoke_role(AGENT_MANAGER, accounts.bob), Ok(()));
+            set_caller(accounts.bob);
+            assert_eq!(register(&mut registry), Err(Error::Unauthorized));
+
+            let role_events = events()
+                .into_iter()
+                .filter(|event| matches!(event, Event::RoleGranted(_) | Event::RoleRevoked(_)))
+                .collect::<Vec<_>>();
+            assert_eq!(role_events.len(), 2);
+            assert!(matches!(&role_events[0], Event::RoleGranted(RoleGranted { role, account, sender })
+                if *role == AGENT_MANAGER && *account == accounts.bob && *sender == accounts.alice));
+            assert!(matches!(&role_events[1], Event::RoleRevoked(RoleRevoked { role, account, sender })
+                if *role == AGENT_MANAGER && *account == accounts.bob && *sender == accounts.alice));
+        }
+
+        #[ink::test]
+        fn holders_renounce_their_own_roles() {
+            let accounts = accounts();
+            let mut registry = registry();
+
+            set_caller(accounts.bob);
+            assert_eq!(registry.renounce_role(AGENT_MANAGER), Err(AccessError::MissingRole));
+            set_caller(accounts.alice);
+            assert_eq!(registry.renounce_role(AGENT_MANAGER), Ok(()));
+            assert!(!registry.has_role(AGENT_MANAGER, accounts.alice));
+            assert_eq!(register(&mut registry), Err(Error::Unauthorized));
+
+            let renounced = events().into_iter().any(|event| {
+                matches!(event, Event::RoleRevoked(RoleRevoked { role, account, sender })
+                    if role == AGENT_MANAGER && account == accounts.alice && sender == accounts.alice)
+            });
+            assert!(renounced);
+        }
+
+        #[ink::test]
+        fn ownership_moves_in_two_steps() {
+            let accounts = accounts();
+            let mut registry = registry();
+
+            set_caller(accounts.bob);
+            assert_eq!(registry.transfer_ownership(accounts.bob), Err(AccessError::NotOwner));
+            set_caller(accounts.alice);
+            assert_eq!(registry.transfer_ownership(accounts.charlie), Ok(()));
+            assert_eq!((registry.owner(), registry.pending_owner()), (accounts.alice, Some(accounts.charlie)));
+
+            set_caller(accounts.bob);
+            assert_eq!(registry.accept_ownership(), Err(AccessError::NotPendingOwner));
+            set_caller(accounts.charlie);
+            assert_eq!(registry.accept_ownership(), Ok(()));
+            assert_eq!((registry.owner(), registry.pending_owner()), (accounts.charlie, None));
+
+            // The new owner administers roles; the old one no longer does
+            set_caller(accounts.alice);
+            assert_eq!(registry.grant_role(AGENT_MANAGER, accounts.bob), Err(AccessError::NotOwner));
+            set_caller(accounts.charlie);
+            assert_eq!(registry.revoke_role(AGENT_MANAGER, accounts.alice), Ok(()));
+            assert!(!registry.has_role(AGENT_MANAGER, accounts.alice));
+        }
+
+        #[ink::test]
+        fn only_governance_upgrades_and_migrates() {
+            let accounts = accounts();
+            let mut registry = registry();
+
+            set_caller(accounts.alice);
+            assert_eq!(registry.set_code(Hash::from([1; 32])), Err(Error::Unauthorized));
+            assert_eq!(registry.migrate(0, 10), Err(Error::Unauthorized));
+
+            set_caller(accounts.charlie);
+            registry.storage_version.set(&(STORAGE_VERSION + 1));
+            assert_eq!(registry.migrate(0, 10), Err(Error::UnsupportedStorageVersion));
+            registry.storage_version.set(&STORAGE_VERSION);
+            assert_eq!(registry.migrate(0, 10), Ok(()));
+        }
+
+        #[ink::test]
+        fn agent_managers_report_savings_per_zone() {
+            let accounts = accounts();
+            let mut registry = registry();
+            let agent_id = register(&mut registry).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(registry.update_performance(agent_id, 500, 0), Err(Error::Unauthorized));
+            set_caller(accounts.alice);
+            assert_eq!(registry.update_performance(agent_id, 500, 0), Ok(()));
+            assert_eq!(registry.update_performance(agent_id, 250, 0), Ok(()));
+            assert_eq!((registry.zone_energy_saved(0), registry.zone_energy_saved(1)), (750, 0));
+        }
+
+        #[ink::test]
+        fn migrate_moves_free_text_zones_to_unknown() {
+            let accounts = accounts();
+            let mut registry = registry();
+            let stored = AIAgentV1 {
+                id: 0,
+                name: String::from("Traffic"),
+                zone: String::from("Downtown"),
+                status: AgentStatus::Active,
+                performance_score: 80,
+                decisions_made: 3,
+                energy_saved: 500,
+                cost_reduction: 40,
+                last_update: 0,
+                specialization: String::from("Routing"),
+                confidence_level: 90,
+            };
+            ink::env::set_contract_storage(&(registry.agents.key(), 0u32), &stored);
+            registry.agent_count = 1;
+            registry.storage_version.set(&1);
+
+            assert_eq!(registry.migrate(0, 10), Err(Error::Unauthorized));
+            set_caller(accounts.charlie);
+            assert_eq!(registry.migrate(0, 10), Ok(()));
+            assert_eq!(registry.get_storage_version(), STORAGE_VERSION);
+
+            let agent = registry.get_agent(0).unwrap();
+            assert_eq!((agent.zone, agent.energy_saved, agent.status), (UNKNOWN_ZONE, 500, AgentStatus::Active));
+            assert_eq!(agent.specialization, "Routing");
+
+            set_caller(accounts.alice);
+            assert_eq!(registry.set_agent_zone(0, 2), Ok(()));
+            assert_eq!(registry.get_agent(0).unwrap().zone, 2);
+        }
+
+        #[ink::test]
+        fn migrations_run_in_ordered_batches() {
+            let accounts = accounts();
+            let mut registry = registry();
+            registry.agent_count = 2;
+            registry.storage_version.set(&1);
+
+            set_caller(accounts.charlie);
+            assert_eq!(registry.migrate(0, 1), Ok(()));
+            assert_eq!((registry.get_migration_cursor(), registry.get_storage_version()), (Some(1), 1));
+            set_caller(accounts.bob);
+            assert_eq!(registry.migrate(0, 1), Err(Error::MigrationOutOfOrder));
+            assert_eq!(registry.migrate(1, 1), Ok(()));
+            assert_eq!((registry.get_migration_cursor(), registry.get_storage_version()), (None, STORAGE_VERSION));
+        }
+    }
+}
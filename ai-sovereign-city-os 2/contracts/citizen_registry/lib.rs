@@ -0,0 +1,379 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod citizen_registry {
+    use city_common::citizenship::CitizenRegistry as CitizenRegistryTrait;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    /// Upper bound on the number of registered attesters.
+    pub const MAX_ATTESTERS: u32 = 32;
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        Unauthorized,
+        NotAttester,
+        AlreadyAttester,
+        TooManyAttesters,
+        InvalidExpiry,
+        IdentityInUse,
+        CredentialNotFound,
+        AlreadyRevoked,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Proof, issued by an attester, that an account belongs to a verified
+    /// citizen.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Credential {
+        /// Hash of the verified identity (e.g. a salted municipal ID number).
+        /// Each identity can back only one account at a time.
+        pub identity: Hash,
+        pub attester: AccountId,
+        pub issued_at: Timestamp,
+        /// Block the account's unbroken citizenship under this identity began
+        /// in. Renewing a valid credential keeps it; moving the identity to
+        /// another account starts over.
+        pub citizen_since: BlockNumber,
+        pub expires_at: Timestamp,
+        pub revoked: bool,
+    }
+
+    impl Credential {
+        fn is_valid(&self, now: Timestamp) -> bool {
+            !self.revoked && now < self.expires_at
+        }
+    }
+
+    #[ink(storage)]
+    pub struct CitizenRegistry {
+        attesters: Vec<AccountId>,
+        credentials: Mapping<AccountId, Credential>,
+        identities: Mapping<Hash, AccountId>,
+        governance: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AttesterAdded {
+        #[ink(topic)]
+        attester: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AttesterRemoved {
+        #[ink(topic)]
+        attester: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct CredentialIssued {
+        #[ink(topic)]
+        citizen: AccountId,
+        #[ink(topic)]
+        attester: AccountId,
+        expires_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct CredentialRevoked {
+        #[ink(topic)]
+        citizen: AccountId,
+        revoked_by: AccountId,
+    }
+
+    impl CitizenRegistry {
+        #[ink(constructor)]
+        pub fn new(governance: AccountId) -> Self {
+            Self {
+                attesters: Vec::new(),
+                credentials: Mapping::default(),
+                identities: Mapping::default(),
+                governance,
+            }
+        }
+
+        #[ink(message)]
+        pub fn add_attester(&mut self, attester: AccountId) -> Result<()> {
+            self.ensure_governance()?;
+            if self.attesters.contains(&attester) {
+                return Err(Error::AlreadyAttester);
+            }
+            if self.attesters.len() >= MAX_ATTESTERS as usize {
+                return Err(Error::TooManyAttesters);
+            }
+
+            self.attesters.push(attester);
+            self.env().emit_event(AttesterAdded { attester });
+            Ok(())
+        }
+
+        /// Removes an attester. Credentials it issued stay valid until they
+        /// expire or are revoked.
+        #[ink(message)]
+        pub fn remove_attester(&mut self, attester: AccountId) -> Result<()> {
+            self.ensure_governance()?;
+            let position = self.attesters.iter().position(|account| *account == attester).ok_or(Error::NotAttester)?;
+            self.attesters.swap_remove(position);
+
+            self.env().emit_event(AttesterRemoved { attester });
+            Ok(())
+        }
+
+        /// Issues or renews the credential of `citizen` for a verified
+        /// `identity`. Fails if the identity already backs another account
+        /// with a valid credential.
+        #[ink(message)]
+        pub fn issue_credential(&mut self, citizen: AccountId, identity: Hash, expires_at: Timestamp) -> Result<()> {
+            let attester = self.env().caller();
+            if !self.attesters.contains(&attester) {
+                return Err(Error::NotAttester);
+            }
+            let now = self.env().block_timestamp();
+            if expires_at <= now {
+                return Err(Error::InvalidExpiry);
+            }
+
+            if let Some(holder) = self.identities.get(identity) {
+                let held = self.credentials.get(holder).is_some_and(|credential| credential.is_valid(now));
+                if holder != citizen && held {
+                    return Err(Error::IdentityInUse);
+                }
+            }
+            let mut citizen_since = self.env().block_number();
+            // Release the identity an earlier credential of this account was
+            // bound to, or keep its start if this renews it in time
+            if let Some(previous) = self.credentials.get(citizen) {
+                if previous.identity != identity {
+                    self.identities.remove(previous.identity);
+                } else if previous.is_valid(now) {
+                    citizen_since = previous.citizen_since;
+                }
+            }
+
+            self.identities.insert(identity, &citizen);
+            self.credentials.insert(
+                citizen,
+                &Credential {
+                    identity,
+                    attester,
+                    issued_at: now,
+                    citizen_since,
+                    expires_at,
+                    revoked: false,
+                },
+            );
+
+            self.env().emit_event(CredentialIssued {
+                citizen,
+                attester,
+                expires_at,
+            });
+
+            Ok(())
+        }
+
+        /// Revokes the credential of `citizen`. Any attester or governance
+        /// may revoke.
+        #[ink(message)]
+        pub fn revoke_credential(&mut self, citizen: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.governance && !self.attesters.contains(&caller) {
+                return Err(Error::Unauthorized);
+            }
+
+            let mut credential = self.credentials.get(citizen).ok_or(Error::CredentialNotFound)?;
+            if credential.revoked {
+                return Err(Error::AlreadyRevoked);
+            }
+            credential.revoked = true;
+            self.credentials.insert(citizen, &credential);
+
+            self.env().emit_event(CredentialRevoked {
+                citizen,
+                revoked_by: caller,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_credential(&self, citizen: AccountId) -> Option<Credential> {
+            self.credentials.get(citizen)
+        }
+
+        #[ink(message)]
+        pub fn get_attesters(&self) -> Vec<AccountId> {
+            self.attesters.clone()
+        }
+
+        #[ink(message)]
+        pub fn get_governance(&self) -> AccountId {
+            self.governance
+        }
+
+        fn ensure_governance(&self) -> Result<()> {
+            if self.env().caller() != self.governance {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+    }
+
+    impl CitizenRegistryTrait for CitizenRegistry {
+        #[ink(message)]
+        fn is_citizen(&self, account: AccountId) -> bool {
+            let now = self.env().block_timestamp();
+            self.credentials.get(account).is_some_and(|credential| credential.is_valid(now))
+        }
+
+        #[ink(message)]
+        fn citizen_since(&self, account: AccountId) -> Option<BlockNumber> {
+            let now = self.env().block_timestamp();
+            self.credentials
+                .get(account)
+                .filter(|credential| credential.is_valid(now))
+                .map(|credential| credential.citizen_since)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        const EXPIRES_AT: Timestamp = 1_000;
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        fn identity(seed: u8) -> Hash {
+            Hash::from([seed; 32])
+        }
+
+        /// Registry governed by Alice, with Bob as its only attester.
+        fn registry() -> CitizenRegistry {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut registry = CitizenRegistry::new(accounts.alice);
+            assert_eq!(registry.add_attester(accounts.bob), Ok(()));
+            registry
+        }
+
+        #[ink::test]
+        fn only_governance_manages_attesters() {
+            let accounts = accounts();
+            let mut registry = registry();
+            assert_eq!(registry.add_attester(accounts.bob), Err(Error::AlreadyAttester));
+            assert_eq!(registry.remove_attester(accounts.charlie), Err(Error::NotAttester));
+
+            set_caller(accounts.bob);
+            assert_eq!(registry.add_attester(accounts.charlie), Err(Error::Unauthorized));
+            assert_eq!(registry.remove_attester(accounts.bob), Err(Error::Unauthorized));
+
+            set_caller(accounts.alice);
+            assert_eq!(registry.add_attester(accounts.charlie), Ok(()));
+            assert_eq!(registry.get_attesters(), vec![accounts.bob, accounts.charlie]);
+            assert_eq!(registry.remove_attester(accounts.bob), Ok(()));
+            assert_eq!(registry.get_attesters(), vec![accounts.charlie]);
+        }
+
+        #[ink::test]
+        fn attesters_issue_credentials_until_they_expire() {
+            let accounts = accounts();
+            let mut registry = registry();
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                registry.issue_credential(accounts.django, identity(1), EXPIRES_AT),
+                Err(Error::NotAttester)
+            );
+            set_caller(accounts.bob);
+            test::set_block_timestamp::<DefaultEnvironment>(100);
+            assert_eq!(registry.issue_credential(accounts.django, identity(1), 100), Err(Error::InvalidExpiry));
+            assert_eq!(registry.issue_credential(accounts.django, identity(1), EXPIRES_AT), Ok(()));
+
+            let credential = registry.get_credential(accounts.django).unwrap();
+            assert_eq!((credential.attester, credential.issued_at), (accounts.bob, 100));
+            assert!(registry.is_citizen(accounts.django));
+            assert!(!registry.is_citizen(accounts.eve));
+
+            test::set_block_timestamp::<DefaultEnvironment>(EXPIRES_AT);
+            assert!(!registry.is_citizen(accounts.django));
+        }
+
+        #[ink::test]
+        fn an_identity_backs_one_valid_credential_at_a_time() {
+            let accounts = accounts();
+            let mut registry = registry();
+            set_caller(accounts.bob);
+            assert_eq!(registry.issue_credential(accounts.django, identity(1), EXPIRES_AT), Ok(()));
+            assert_eq!(
+                registry.issue_credential(accounts.eve, identity(1), EXPIRES_AT),
+                Err(Error::IdentityInUse)
+            );
+
+            // Renewing under a new identity frees the old one
+            assert_eq!(registry.issue_credential(accounts.django, identity(2), EXPIRES_AT), Ok(()));
+            assert_eq!(registry.issue_credential(accounts.eve, identity(1), EXPIRES_AT), Ok(()));
+            assert!(registry.is_citizen(accounts.django) && registry.is_citizen(accounts.eve));
+        }
+
+        #[ink::test]
+        fn attesters_and_governance_revoke_credentials() {
+            let accounts = accounts();
+            let mut registry = registry();
+            set_caller(accounts.bob);
+            assert_eq!(registry.issue_credential(accounts.django, identity(1), EXPIRES_AT), Ok(()));
+            assert_eq!(registry.issue_credential(accounts.eve, identity(2), EXPIRES_AT), Ok(()));
+
+            set_caller(accounts.charlie);
+            assert_eq!(registry.revoke_credential(accounts.django), Err(Error::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(registry.revoke_credential(accounts.django), Ok(()));
+            assert_eq!(registry.revoke_credential(accounts.django), Err(Error::AlreadyRevoked));
+            assert_eq!(registry.revoke_credential(accounts.frank), Err(Error::CredentialNotFound));
+            set_caller(accounts.alice);
+            assert_eq!(registry.revoke_credential(accounts.eve), Ok(()));
+
+            assert!(!registry.is_citizen(accounts.django) && !registry.is_citizen(accounts.eve));
+            assert!(registry.get_credential(accounts.django).unwrap().revoked);
+        }
+
+        #[ink::test]
+        fn renewals_keep_the_citizenship_start_but_moves_reset_it() {
+            let accounts = accounts();
+            let mut registry = registry();
+            set_caller(accounts.bob);
+            assert_eq!(registry.issue_credential(accounts.django, identity(1), EXPIRES_AT), Ok(()));
+            assert_eq!(registry.citizen_since(accounts.django), Some(0));
+            assert_eq!(registry.citizen_since(accounts.eve), None);
+
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(registry.issue_credential(accounts.django, identity(1), 2 * EXPIRES_AT), Ok(()));
+            assert_eq!(registry.citizen_since(accounts.django), Some(0));
+
+            // The identity moves to a fresh account, which starts over
+            assert_eq!(registry.revoke_credential(accounts.django), Ok(()));
+            assert_eq!(registry.citizen_since(accounts.django), None);
+            assert_eq!(registry.issue_credential(accounts.eve, identity(1), 2 * EXPIRES_AT), Ok(()));
+            assert_eq!(registry.citizen_since(accounts.eve), Some(1));
+
+            // So does a new credential after a revocation
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(registry.issue_credential(accounts.django, identity(2), 2 * EXPIRES_AT), Ok(()));
+            assert_eq!(registry.citizen_since(accounts.django), Some(2));
+        }
+    }
+}
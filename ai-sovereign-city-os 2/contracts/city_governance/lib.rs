@@ -0,0 +1,3586 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod city_governance {
+    use city_common::access::{AccessControl, AccessControlled, AccessError, RoleId, PAUSER, TREASURER};
+    use city_common::call::{invoke, CallFailure};
+    use city_common::citizenship::CitizenRegistry;
+    use city_common::pausable::{FunctionId, PauseError, PauseState, Pausable};
+    use city_common::psp22::PSP22Votes;
+    use city_common::treasury::{Asset, Treasury};
+    use city_common::zones::ZoneDirectory;
+    use city_common::ZoneId;
+    use ink::contract_ref;
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use ink::storage::traits::StorageKey;
+    use ink::storage::{Lazy, Mapping};
+
+    /// Upper bound on the number of calls a single proposal may carry.
+    pub const MAX_PROPOSAL_CALLS: u32 = 8;
+    /// Longest delegation chain (in hops) whose power reaches the final delegate.
+    pub const MAX_DELEGATION_DEPTH: u32 = 2;
+    /// Upper bound on the direct delegators of a single account.
+    pub const MAX_DELEGATORS: u32 = 16;
+    /// Default length of a voice-credit epoch: 30 days.
+    pub const DEFAULT_EPOCH_LENGTH: u64 = 2_592_000_000;
+    /// Default voice credits each citizen receives per epoch.
+    pub const DEFAULT_VOICE_CREDITS: u128 = 100;
+    /// Upper bound on the entries returned by a single listing query.
+    pub const MAX_PAGE_SIZE: u32 = 50;
+    /// Layout version of this contract's storage. Bump it together with a
+    /// step in `migrate` whenever stored types change. Fields added later
+    /// must live outside the root cell (in a `Lazy` or `Mapping`) so the
+    /// root still decodes after `set_code`.
+    pub const STORAGE_VERSION: u32 = 2;
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        ProposalNotFound,
+        ProposalNotActive,
+        AlreadyVoted,
+        VotingPeriodNotEnded,
+        VotingPeriodEnded,
+        NoVotingPower,
+        ProposalNotQueued,
+        TimelockNotElapsed,
+        Unauthorized,
+        TooManyCalls,
+        InvalidTiming,
+        SelfDelegation,
+        DelegationCycle,
+        DelegationTooDeep,
+        TooManyDelegators,
+        NotDelegating,
+        NotVoted,
+        AnalysisAlreadySubmitted,
+        InvalidScore,
+        NotProposer,
+        VotesAlreadyCast,
+        VetoWindowClosed,
+        InvalidFunding,
+        TreasuryNotSet,
+        BudgetExceedsTreasury,
+        WrongVotingScheme,
+        InsufficientVoiceCredits,
+        InvalidVoiceCredits,
+        InvalidDecay,
+        BelowProposalThreshold,
+        WrongDeposit,
+        DepositTransferFailed,
+        NotCitizen,
+        UpgradeFailed,
+        UnsupportedStorageVersion,
+        MigrationOutOfOrder,
+        Paused,
+        PauseCoolingDown,
+        ZoneRegistryNotSet,
+        ZoneNotFound,
+        NotResident,
+        /// A call of a proposal being executed failed, reverting the batch.
+        CallFailed { call_index: u32, reason: CallFailure },
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl From<PauseError> for Error {
+        fn from(error: PauseError) -> Self {
+            match error {
+                PauseError::Paused => Error::Paused,
+                PauseError::Unauthorized => Error::Unauthorized,
+                PauseError::CoolingDown => Error::PauseCoolingDown,
+            }
+        }
+    }
+
+    impl From<AccessError> for Error {
+        fn from(_: AccessError) -> Self {
+            Error::Unauthorized
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ProposalStatus {
+        Active,
+        Passed,
+        Rejected,
+        Executed,
+        ExecutionFailed { call_index: u32, reason: CallFailure },
+        Expired,
+        Cancelled,
+        Vetoed,
+        /// Replaced by an amended revision, linked through `superseded_by`.
+        Superseded,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum VoteType {
+        For,
+        Against,
+        /// Counts towards quorum without taking a side.
+        Abstain,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum RiskLevel {
+        Low,
+        Medium,
+        High,
+        Critical,
+    }
+
+    /// The AI oracle's assessment of a proposal.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct AiRecommendation {
+        /// Expected success of the proposal, 0-100.
+        pub score: u32,
+        pub risk_level: RiskLevel,
+        /// Hash of the full off-chain analysis report.
+        pub summary_hash: Hash,
+        pub submitted_at: u64,
+    }
+
+    /// How votes on a proposal are weighted.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum VotingScheme {
+        /// Voting power (reputation or token balance, plus delegations) counts as-is.
+        Linear,
+        /// Voters spend voice credits; the integer square root of the credits
+        /// spent is counted. Delegations do not apply.
+        Quadratic,
+    }
+
+    /// Voice credits per citizen and how often they are replenished.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct VoiceCreditConfig {
+        /// Epoch length in milliseconds.
+        pub epoch_length: u64,
+        pub credits_per_epoch: u128,
+    }
+
+    /// What it takes to open a proposal.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ProposalRequirements {
+        /// Minimum voting-token balance in token mode, minimum effective
+        /// reputation otherwise.
+        pub threshold: u128,
+        /// Native deposit sent with `create_proposal`, refunded if the
+        /// proposal reaches quorum and sent to the treasury if not.
+        pub deposit: Balance,
+    }
+
+    /// How reputation fades while a citizen is inactive. Decay is applied
+    /// lazily, from `last_active` to the time the profile is read.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ReputationDecay {
+        None,
+        /// Halves every `half_life` milliseconds, linearly in between.
+        Exponential { half_life: u64 },
+        /// Loses `rate_bps` basis points of what is left every full `period`.
+        Step { period: u64, rate_bps: u32 },
+    }
+
+    impl ReputationDecay {
+        fn is_valid(&self) -> bool {
+            match *self {
+                ReputationDecay::None => true,
+                ReputationDecay::Exponential { half_life } => half_life > 0,
+                ReputationDecay::Step { period, rate_bps } => period > 0 && rate_bps <= 10_000,
+            }
+        }
+
+        /// Reputation left of `score` after `elapsed` milliseconds.
+        fn apply(&self, score: u32, elapsed: u64) -> u32 {
+            match *self {
+                ReputationDecay::None => score,
+                ReputationDecay::Exponential { half_life } => {
+                    let halvings = elapsed / half_life;
+                    if halvings >= 32 {
+                        return 0;
+                    }
+                    let halved = score >> halvings;
+                    let partial = halved as u128 * (elapsed % half_life) as u128
+                        / (2 * half_life as u128);
+                    halved - partial as u32
+                }
+                ReputationDecay::Step { period, rate_bps } => {
+                    // Exponentiation by squaring of the retained fraction
+                    let (mut score, mut retained) = (score as u128, (10_000 - rate_bps) as u128);
+                    let mut periods = elapsed / period;
+                    while periods > 0 && score > 0 {
+                        if periods & 1 == 1 {
+                            score = score * retained / 10_000;
+                        }
+                        retained = retained * retained / 10_000;
+                        periods >>= 1;
+                    }
+                    score as u32
+                }
+            }
+        }
+    }
+
+    /// Voice credits a citizen has spent in `epoch`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CreditUsage {
+        pub epoch: u64,
+        pub spent: u128,
+    }
+
+    /// How a proposal's budget is paid out of the city treasury.
+    ///
+    /// The first milestone is paid when the proposal executes. Later ones are
+    /// released by proposals calling `release_next_tranche` on the treasury.
+    /// With `vesting` set, the budget is instead streamed to the beneficiary.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Funding {
+        pub asset: Asset,
+        pub beneficiary: AccountId,
+        /// Tranche amounts summing to the budget; empty pays it in one go.
+        pub milestones: Vec<Balance>,
+        pub vesting: Option<Vesting>,
+    }
+
+    /// Linear vesting schedule, in milliseconds after the proposal executes.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Vesting {
+        pub cliff: Timestamp,
+        pub duration: Timestamp,
+    }
+
+    impl Funding {
+        fn tranches(&self, budget: Balance) -> Vec<Balance> {
+            if self.milestones.is_empty() {
+                ink::prelude::vec![budget]
+            } else {
+                self.milestones.clone()
+            }
+        }
+    }
+
+    /// Durations (in milliseconds) governing a proposal's lifecycle.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ProposalTiming {
+        /// How long a proposal accepts votes after creation.
+        pub voting_period: u64,
+        /// Delay between a proposal passing and becoming executable.
+        pub timelock_delay: u64,
+        /// How long a queued proposal stays executable before it expires.
+        pub execution_window: u64,
+    }
+
+    /// A cross-contract call dispatched when a passed proposal is executed.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ProposalCall {
+        pub callee: AccountId,
+        pub selector: [u8; 4],
+        /// SCALE-encoded message arguments, appended after the selector as-is.
+        pub input: Vec<u8>,
+        pub transferred_value: Balance,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Proposal {
+        pub id: u32,
+        pub title: String,
+        pub description: String,
+        pub proposer: AccountId,
+        pub votes_for: u128,
+        pub votes_against: u128,
+        pub votes_abstain: u128,
+        pub status: ProposalStatus,
+        pub voting_scheme: VotingScheme,
+        pub voting_ends_at: u64,
+        pub executable_at: u64, // set once the proposal is queued
+        pub expires_at: u64,
+        pub budget: u128,
+        /// Deposit held until the proposal is tallied.
+        pub deposit: Balance,
+        /// Treasury payout of the budget; `None` leaves the budget informational.
+        pub funding: Option<Funding>,
+        /// Filled in by the AI oracle after `AnalysisRequested` is emitted.
+        pub ai_recommendation: Option<AiRecommendation>,
+        pub calls: Vec<ProposalCall>,
+        /// PSP22 token whose balances before `snapshot_block` weigh votes;
+        /// `None` for reputation voting.
+        pub voting_token: Option<AccountId>,
+        /// Total voting supply at creation, used for the quorum; the zone's
+        /// population for zone-local proposals.
+        pub voting_supply: u128,
+        /// Zone whose residents vote on the proposal; `None` for city-wide ones.
+        pub zone: Option<ZoneId>,
+        /// Citizens whose power is in the tallies, directly or through a
+        /// delegate, for the zone-local quorum.
+        pub voter_count: u32,
+        /// Proposal this one amends, if it is a revision.
+        pub revision_of: Option<u32>,
+        pub superseded_by: Option<u32>,
+        /// Block the proposal was created in; voting power is read as it
+        /// stood before this block.
+        pub snapshot_block: BlockNumber,
+    }
+
+    /// `Proposal` as stored before storage version 2, without zones.
+    #[derive(scale::Encode, scale::Decode)]
+    struct ProposalV1 {
+        id: u32,
+        title: String,
+        description: String,
+        proposer: AccountId,
+        votes_for: u128,
+        votes_against: u128,
+        votes_abstain: u128,
+        status: ProposalStatus,
+        voting_scheme: VotingScheme,
+        voting_ends_at: u64,
+        executable_at: u64,
+        expires_at: u64,
+        budget: u128,
+        deposit: Balance,
+        funding: Option<Funding>,
+        ai_recommendation: Option<AiRecommendation>,
+        calls: Vec<ProposalCall>,
+        voting_token: Option<AccountId>,
+        voting_supply: u128,
+        revision_of: Option<u32>,
+        superseded_by: Option<u32>,
+        snapshot_block: BlockNumber,
+    }
+
+    impl From<ProposalV1> for Proposal {
+        fn from(proposal: ProposalV1) -> Self {
+            Self {
+                id: proposal.id,
+                title: proposal.title,
+                description: proposal.description,
+                proposer: proposal.proposer,
+                votes_for: proposal.votes_for,
+                votes_against: proposal.votes_against,
+                votes_abstain: proposal.votes_abstain,
+                status: proposal.status,
+                voting_scheme: proposal.voting_scheme,
+                voting_ends_at: proposal.voting_ends_at,
+                executable_at: proposal.executable_at,
+                expires_at: proposal.expires_at,
+                budget: proposal.budget,
+                deposit: proposal.deposit,
+                funding: proposal.funding,
+                ai_recommendation: proposal.ai_recommendation,
+                calls: proposal.calls,
+                voting_token: proposal.voting_token,
+                voting_supply: proposal.voting_supply,
+                zone: None,
+                voter_count: 0,
+                revision_of: proposal.revision_of,
+                superseded_by: proposal.superseded_by,
+                snapshot_block: proposal.snapshot_block,
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CitizenProfile {
+        pub reputation_score: u32,
+        pub voting_power: u32,
+        pub proposals_submitted: u32,
+        pub votes_cast: u32,
+        pub ai_contributions: u32,
+        /// Time of the citizen's last rewarded activity; reputation decays from here.
+        pub last_active: Timestamp,
+    }
+
+    /// Voting power an account held from `block` onwards.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Checkpoint {
+        pub block: BlockNumber,
+        pub voting_power: u32,
+    }
+
+    /// A citizen's direct vote on a proposal and the power it carries,
+    /// including power delegated to them.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct VoteRecord {
+        pub vote: VoteType,
+        pub voting_power: u128,
+    }
+
+    /// A citizen's power counted in the vote of `caster` (themselves or a delegate).
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CastPower {
+        pub caster: AccountId,
+        pub power: u128,
+        /// Withdrawals the caster had made when this power was cast; a later
+        /// withdrawal already took it out of the tallies.
+        pub round: u32,
+    }
+
+    #[ink(storage)]
+    pub struct CityGovernance {
+        proposals: Mapping<u32, Proposal>,
+        citizen_profiles: Mapping<AccountId, CitizenProfile>,
+        checkpoints: Mapping<(AccountId, u32), Checkpoint>, // (citizen, index) -> checkpoint
+        checkpoint_counts: Mapping<AccountId, u32>,
+        proposals_of: Mapping<(AccountId, u32), u32>, // (proposer, index) -> proposal id
+        proposals_of_counts: Mapping<AccountId, u32>,
+        votes: Mapping<(u32, AccountId), VoteType>, // (proposal_id, voter) -> vote
+        vote_weights: Mapping<(u32, AccountId), u128>, // (proposal_id, voter) -> weight cast
+        withdrawals: Mapping<(u32, AccountId), u32>, // (proposal_id, voter) -> withdrawn votes
+        cast_power: Mapping<(u32, AccountId), CastPower>, // (proposal_id, citizen) -> who cast their power
+        delegates: Mapping<AccountId, AccountId>,
+        delegators: Mapping<AccountId, Vec<AccountId>>,
+        credit_usage: Mapping<AccountId, CreditUsage>,
+        deposit_refunds: Mapping<AccountId, Balance>, // proposer -> refunds not yet claimed
+        proposal_count: u32,
+        total_supply: u128,
+        voting_token: Option<AccountId>,
+        access: AccessControl,
+        pauses: PauseState,
+        ai_oracle: AccountId,
+        /// Safety council (typically a multisig) allowed to veto queued proposals.
+        guardian: Option<AccountId>,
+        treasury: Option<AccountId>,
+        /// Registry whose credentials are required to vote; while `None`,
+        /// `open_voting` decides.
+        citizen_registry: Option<AccountId>,
+        timing: ProposalTiming,
+        voice_credits: VoiceCreditConfig,
+        reputation_decay: ReputationDecay,
+        requirements: ProposalRequirements,
+        storage_version: Lazy<u32>,
+        /// Registry zone-local proposals read residency from; added in
+        /// storage version 2.
+        zone_registry: Lazy<Option<AccountId>>,
+        /// Proposal whose calls `run_proposal` may run next.
+        executing: Lazy<Option<u32>>,
+        /// Next proposal `migrate` converts; `None` while no migration runs.
+        migration_cursor: Lazy<Option<u32>>,
+        /// Citizens a vote counts towards `voter_count`, the voter included.
+        vote_heads: Mapping<(u32, AccountId), u32>,
+        /// Whether every account may vote while no citizen registry is set.
+        /// Chosen at deployment; deployments from before it existed read as
+        /// open.
+        open_voting: Lazy<bool>,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        function: FunctionId,
+        account: AccountId,
+        until: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        function: FunctionId,
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        code_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct StorageMigrated {
+        from: u32,
+        to: u32,
+    }
+
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        pending_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        title: String,
+    }
+
+    /// Asks the AI oracle to analyse a new proposal and answer through
+    /// `submit_ai_analysis`.
+    #[ink(event)]
+    pub struct AnalysisRequested {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        oracle: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AnalysisSubmitted {
+        #[ink(topic)]
+        proposal_id: u32,
+        score: u32,
+        risk_level: RiskLevel,
+        summary_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct VoteCast {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        voter: AccountId,
+        vote: VoteType,
+        voting_power: u128,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        from_delegate: Option<AccountId>,
+        to_delegate: Option<AccountId>,
+    }
+
+    /// A delegator voted directly and took their power back from the
+    /// delegate who had already cast it.
+    #[ink(event)]
+    pub struct DelegatedVoteOverridden {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        delegate: AccountId,
+        delegator: AccountId,
+        voting_power: u128,
+    }
+
+    #[ink(event)]
+    pub struct ProposalQueued {
+        #[ink(topic)]
+        proposal_id: u32,
+        executable_at: u64,
+        expires_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct TimingUpdated {
+        timing: ProposalTiming,
+    }
+
+    #[ink(event)]
+    pub struct VoiceCreditsUpdated {
+        config: VoiceCreditConfig,
+    }
+
+    #[ink(event)]
+    pub struct ProposalRequirementsUpdated {
+        requirements: ProposalRequirements,
+    }
+
+    /// A proposal deposit was refunded to its proposer, or slashed to the
+    /// treasury.
+    #[ink(event)]
+    pub struct DepositSettled {
+        #[ink(topic)]
+        proposal_id: u32,
+        amount: Balance,
+        refunded: bool,
+    }
+
+    /// A proposer collected their refunded deposits.
+    #[ink(event)]
+    pub struct DepositClaimed {
+        #[ink(topic)]
+        proposer: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ReputationDecayUpdated {
+        decay: ReputationDecay,
+    }
+
+    #[ink(event)]
+    pub struct VotingTokenUpdated {
+        voting_token: Option<AccountId>,
+    }
+
+    /// A voter moved their vote to another option, or withdrew it (`vote: None`).
+    #[ink(event)]
+    pub struct VoteChanged {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        voter: AccountId,
+        previous: VoteType,
+        vote: Option<VoteType>,
+        voting_power: u128,
+    }
+
+    #[ink(event)]
+    pub struct ProposalAmended {
+        #[ink(topic)]
+        proposal_id: u32,
+        #[ink(topic)]
+        revision_id: u32,
+    }
+
+    #[ink(event)]
+    pub struct TreasuryUpdated {
+        treasury: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct CitizenRegistryUpdated {
+        citizen_registry: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct ZoneRegistryUpdated {
+        zone_registry: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct GuardianUpdated {
+        guardian: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        proposal_id: u32,
+        status: ProposalStatus,
+    }
+
+    impl CityGovernance {
+        /// Deploys governance without a citizen registry. `open_voting` lets
+        /// every account vote until one is wired in; without it nobody can
+        /// vote before the deployer calls `set_citizen_registry`.
+        #[ink(constructor)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            total_supply: u128,
+            voting_token: Option<AccountId>,
+            ai_oracle: AccountId,
+            guardian: Option<AccountId>,
+            open_voting: bool,
+            voting_period: u64,
+            timelock_delay: u64,
+            execution_window: u64,
+        ) -> Self {
+            let caller = Self::env().caller();
+            let timing = ProposalTiming {
+                voting_period,
+                timelock_delay,
+                execution_window,
+            };
+            assert!(Self::is_valid_timing(&timing), "invalid proposal timing");
+            let mut access = AccessControl::new(caller);
+            access.set_role(TREASURER, caller);
+            access.set_role(PAUSER, caller);
+            let mut storage_version = Lazy::new();
+            storage_version.set(&STORAGE_VERSION);
+            let mut open_voting_choice = Lazy::new();
+            open_voting_choice.set(&open_voting);
+            Self {
+                proposals: Mapping::default(),
+                citizen_profiles: Mapping::default(),
+                checkpoints: Mapping::default(),
+                checkpoint_counts: Mapping::default(),
+                proposals_of: Mapping::default(),
+                proposals_of_counts: Mapping::default(),
+                votes: Mapping::default(),
+                vote_weights: Mapping::default(),
+                withdrawals: Mapping::default(),
+                cast_power: Mapping::default(),
+                delegates: Mapping::default(),
+                delegators: Mapping::default(),
+                credit_usage: Mapping::default(),
+                deposit_refunds: Mapping::default(),
+                proposal_count: 0,
+                total_supply,
+                voting_token,
+                access,
+                pauses: PauseState::default(),
+                ai_oracle,
+                guardian,
+                treasury: None,
+                citizen_registry: None,
+                timing,
+                voice_credits: VoiceCreditConfig {
+                    epoch_length: DEFAULT_EPOCH_LENGTH,
+                    credits_per_epoch: DEFAULT_VOICE_CREDITS,
+                },
+                reputation_decay: ReputationDecay::None,
+                requirements: ProposalRequirements { threshold: 0, deposit: 0 },
+                storage_version,
+                zone_registry: Lazy::new(),
+                executing: Lazy::new(),
+                migration_cursor: Lazy::new(),
+                vote_heads: Mapping::default(),
+                open_voting: open_voting_choice,
+            }
+        }
+
+        /// Opens a proposal. The caller must meet the proposal threshold and
+        /// send exactly the required deposit.
+        #[ink(message, payable)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn create_proposal(
+            &mut self,
+            title: String,
+            description: String,
+            budget: u128,
+            funding: Option<Funding>,
+            calls: Vec<ProposalCall>,
+            voting_scheme: VotingScheme,
+            zone: Option<ZoneId>,
+        ) -> Result<u32> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("create_proposal"))?;
+            if calls.len() > MAX_PROPOSAL_CALLS as usize {
+                return Err(Error::TooManyCalls);
+            }
+            self.ensure_affordable(budget, funding.as_ref())?;
+
+            let caller = self.env().caller();
+            if self.proposer_standing(caller) < self.requirements.threshold {
+                return Err(Error::BelowProposalThreshold);
+            }
+            let deposit = self.env().transferred_value();
+            if deposit != self.requirements.deposit {
+                return Err(Error::WrongDeposit);
+            }
+
+            let proposal_id = self.insert_proposal(
+                caller,
+                title,
+                description,
+                budget,
+                deposit,
+                funding,
+                calls,
+                voting_scheme,
+                zone,
+                None,
+            )?;
+
+            // Update citizen profile
+            self.update_profile(caller, |profile| {
+                profile.proposals_submitted += 1;
+                profile.reputation_score = profile.reputation_score.saturating_add(100); // Reward for proposal creation
+            });
+
+            Ok(proposal_id)
+        }
+
+        /// Withdraws a proposal. Only its proposer may cancel it, and only
+        /// while no votes are counted on it.
+        #[ink(message)]
+        pub fn cancel_proposal(&mut self, proposal_id: u32) -> Result<()> {
+            let mut proposal = self.open_proposal(proposal_id)?;
+            if self.env().caller() != proposal.proposer {
+                return Err(Error::NotProposer);
+            }
+            if proposal.votes_for + proposal.votes_against + proposal.votes_abstain > 0 {
+                return Err(Error::VotesAlreadyCast);
+            }
+
+            // Withdrawing a proposal forfeits its deposit like missing quorum
+            self.settle_deposit(&mut proposal, false);
+            proposal.status = ProposalStatus::Cancelled;
+            self.proposals.insert(proposal_id, &proposal);
+
+            self.env().emit_event(ProposalExecuted {
+                proposal_id,
+                status: proposal.status,
+            });
+
+            Ok(())
+        }
+
+        /// Stops a queued proposal before it executes. Only the guardian may
+        /// veto, and only during the timelock.
+        #[ink(message)]
+        pub fn veto_proposal(&mut self, proposal_id: u32) -> Result<()> {
+            if self.guardian != Some(self.env().caller()) {
+                return Err(Error::Unauthorized);
+            }
+
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if proposal.status != ProposalStatus::Passed {
+                return Err(Error::ProposalNotQueued);
+            }
+            if self.env().block_timestamp() >= proposal.executable_at {
+                return Err(Error::VetoWindowClosed);
+            }
+
+            proposal.status = ProposalStatus::Vetoed;
+            self.proposals.insert(proposal_id, &proposal);
+            self.cancel_reservation(&proposal);
+
+            self.env().emit_event(ProposalExecuted {
+                proposal_id,
+                status: proposal.status,
+            });
+
+            Ok(())
+        }
+
+        /// Replaces an active proposal with a revised one. The original is
+        /// marked `Superseded`, and voting starts over on the revision.
+        #[ink(message)]
+        pub fn amend_proposal(
+            &mut self,
+            proposal_id: u32,
+            title: String,
+            description: String,
+            budget: u128,
+            funding: Option<Funding>,
+            calls: Vec<ProposalCall>,
+        ) -> Result<u32> {
+            let caller = self.env().caller();
+            let mut proposal = self.open_proposal(proposal_id)?;
+            if caller != proposal.proposer {
+                return Err(Error::NotProposer);
+            }
+            if calls.len() > MAX_PROPOSAL_CALLS as usize {
+                return Err(Error::TooManyCalls);
+            }
+            self.ensure_affordable(budget, funding.as_ref())?;
+
+            let revision_id = self.insert_proposal(
+                caller,
+                title,
+                description,
+                budget,
+                proposal.deposit,
+                funding,
+                calls,
+                proposal.voting_scheme.clone(),
+                proposal.zone,
+                Some(proposal_id),
+            )?;
+            // The deposit moves over to the revision
+            proposal.deposit = 0;
+            proposal.status = ProposalStatus::Superseded;
+            proposal.superseded_by = Some(revision_id);
+            self.proposals.insert(proposal_id, &proposal);
+
+            self.env().emit_event(ProposalAmended {
+                proposal_id,
+                revision_id,
+            });
+
+            Ok(revision_id)
+        }
+
+        #[ink(message)]
+        pub fn vote(&mut self, proposal_id: u32, vote: VoteType) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("vote"))?;
+            let caller = self.env().caller();
+            
+            // Check if proposal exists and is active
+            let mut proposal = self.open_proposal(proposal_id)?;
+            if proposal.voting_scheme != VotingScheme::Linear {
+                return Err(Error::WrongVotingScheme);
+            }
+
+            // Check if already voted
+            if self.votes.contains((proposal_id, caller)) {
+                return Err(Error::AlreadyVoted);
+            }
+            if !self.is_citizen(&proposal, caller) {
+                return Err(Error::NotCitizen);
+            }
+            if !self.is_resident(&proposal, caller) {
+                return Err(Error::NotResident);
+            }
+
+            // Get citizen's voting power, including power delegated to them
+            let (voting_power, heads) = self.collect_voting_power(&mut proposal, caller);
+            if voting_power == 0 {
+                return Err(Error::NoVotingPower);
+            }
+
+            self.record_vote(&mut proposal, caller, vote, voting_power, heads);
+
+            Ok(())
+        }
+
+        /// Votes on a quadratic proposal by spending `credits` voice credits
+        /// from the caller's budget for the current epoch.
+        #[ink(message)]
+        pub fn vote_with_credits(&mut self, proposal_id: u32, vote: VoteType, credits: u128) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("vote_with_credits"))?;
+            let caller = self.env().caller();
+
+            let mut proposal = self.open_proposal(proposal_id)?;
+            if proposal.voting_scheme != VotingScheme::Quadratic {
+                return Err(Error::WrongVotingScheme);
+            }
+            if self.votes.contains((proposal_id, caller)) {
+                return Err(Error::AlreadyVoted);
+            }
+            if !self.is_citizen(&proposal, caller) {
+                return Err(Error::NotCitizen);
+            }
+            if !self.is_resident(&proposal, caller) {
+                return Err(Error::NotResident);
+            }
+
+            let voting_power = isqrt(credits);
+            if voting_power == 0 {
+                return Err(Error::InvalidVoiceCredits);
+            }
+            let mut usage = self.current_credit_usage(caller);
+            // The budget may have been lowered below what was already spent
+            if credits > self.voice_credits.credits_per_epoch.saturating_sub(usage.spent) {
+                return Err(Error::InsufficientVoiceCredits);
+            }
+            usage.spent += credits;
+            self.credit_usage.insert(caller, &usage);
+
+            let round = self.withdrawals.get((proposal_id, caller)).unwrap_or(0);
+            self.cast_power.insert((proposal_id, caller), &CastPower { caster: caller, power: voting_power, round });
+            self.record_vote(&mut proposal, caller, vote, voting_power, 1);
+
+            Ok(())
+        }
+
+        /// Moves the caller's vote, with its full weight, to another option.
+        #[ink(message)]
+        pub fn change_vote(&mut self, proposal_id: u32, vote: VoteType) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("change_vote"))?;
+            let caller = self.env().caller();
+            let mut proposal = self.open_proposal(proposal_id)?;
+            let previous = self.votes.get((proposal_id, caller)).ok_or(Error::NotVoted)?;
+            let voting_power = self.vote_weights.get((proposal_id, caller)).unwrap_or(0);
+
+            *proposal.tally_mut(previous) -= voting_power;
+            *proposal.tally_mut(vote) += voting_power;
+            self.votes.insert((proposal_id, caller), &vote);
+            self.proposals.insert(proposal_id, &proposal);
+
+            self.env().emit_event(VoteChanged {
+                proposal_id,
+                voter: caller,
+                previous,
+                vote: Some(vote),
+                voting_power,
+            });
+
+            Ok(())
+        }
+
+        /// Takes the caller's vote back out of the tallies. Delegated power it
+        /// carried returns to the delegators, and the caller may vote again
+        /// without a second reward. Spent voice credits are not refunded.
+        #[ink(message)]
+        pub fn withdraw_vote(&mut self, proposal_id: u32) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("withdraw_vote"))?;
+            let caller = self.env().caller();
+            let mut proposal = self.open_proposal(proposal_id)?;
+            let previous = self.votes.take((proposal_id, caller)).ok_or(Error::NotVoted)?;
+            let voting_power = self.vote_weights.take((proposal_id, caller)).unwrap_or(0);
+            let heads = self.vote_heads.take((proposal_id, caller)).unwrap_or(1);
+
+            *proposal.tally_mut(previous) -= voting_power;
+            proposal.voter_count = proposal.voter_count.saturating_sub(heads);
+            self.proposals.insert(proposal_id, &proposal);
+            let withdrawals = self.withdrawals.get((proposal_id, caller)).unwrap_or(0);
+            self.withdrawals.insert((proposal_id, caller), &(withdrawals + 1));
+
+            self.env().emit_event(VoteChanged {
+                proposal_id,
+                voter: caller,
+                previous,
+                vote: None,
+                voting_power,
+            });
+
+            Ok(())
+        }
+
+        /// Voice credits `citizen` can still spend in the current epoch.
+        #[ink(message)]
+        pub fn voice_credits_remaining(&self, citizen: AccountId) -> u128 {
+            self.voice_credits.credits_per_epoch.saturating_sub(self.current_credit_usage(citizen).spent)
+        }
+
+        #[ink(message)]
+        pub fn get_voice_credit_config(&self) -> VoiceCreditConfig {
+            self.voice_credits.clone()
+        }
+
+        /// Updates the voice-credit budget and epoch length. Only reachable
+        /// through an executed proposal.
+        #[ink(message)]
+        pub fn set_voice_credit_config(&mut self, config: VoiceCreditConfig) -> Result<()> {
+            self.ensure_governance()?;
+            if config.epoch_length == 0 {
+                return Err(Error::InvalidVoiceCredits);
+            }
+
+            self.voice_credits = config.clone();
+            self.env().emit_event(VoiceCreditsUpdated { config });
+            Ok(())
+        }
+
+        /// Delegates the caller's voting power to `to`, replacing any current
+        /// delegate. Power flows on along `to`'s own delegation chain.
+        #[ink(message)]
+        pub fn delegate(&mut self, to: AccountId) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("delegate"))?;
+            let caller = self.env().caller();
+            if to == caller {
+                return Err(Error::SelfDelegation);
+            }
+            let previous = self.delegates.get(caller);
+            if previous == Some(to) {
+                return Ok(());
+            }
+
+            // Walk up from `to`, rejecting cycles and over-long chains
+            let mut hops_above = 0;
+            let mut current = to;
+            while let Some(next) = self.delegates.get(current) {
+                if next == caller {
+                    return Err(Error::DelegationCycle);
+                }
+                hops_above += 1;
+                if hops_above >= MAX_DELEGATION_DEPTH {
+                    return Err(Error::DelegationTooDeep);
+                }
+                current = next;
+            }
+            if self.delegation_height(caller, 0) + 1 + hops_above > MAX_DELEGATION_DEPTH {
+                return Err(Error::DelegationTooDeep);
+            }
+
+            let mut delegators = self.delegators.get(to).unwrap_or_default();
+            if delegators.len() >= MAX_DELEGATORS as usize {
+                return Err(Error::TooManyDelegators);
+            }
+
+            if let Some(previous) = previous {
+                self.remove_delegator(previous, caller);
+            }
+            delegators.push(caller);
+            self.delegators.insert(to, &delegators);
+            self.delegates.insert(caller, &to);
+
+            self.env().emit_event(DelegateChanged {
+                delegator: caller,
+                from_delegate: previous,
+                to_delegate: Some(to),
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn undelegate(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let previous = self.delegates.take(caller).ok_or(Error::NotDelegating)?;
+            self.remove_delegator(previous, caller);
+
+            self.env().emit_event(DelegateChanged {
+                delegator: caller,
+                from_delegate: Some(previous),
+                to_delegate: None,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_delegate(&self, citizen: AccountId) -> Option<AccountId> {
+            self.delegates.get(citizen)
+        }
+
+        #[ink(message)]
+        pub fn get_delegators(&self, citizen: AccountId) -> Vec<AccountId> {
+            self.delegators.get(citizen).unwrap_or_default()
+        }
+
+        /// Tallies a proposal whose voting period is over and, if it passed,
+        /// queues it behind the timelock.
+        #[ink(message)]
+        pub fn queue_proposal(&mut self, proposal_id: u32) -> Result<()> {
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+
+            if proposal.status != ProposalStatus::Active {
+                return Err(Error::ProposalNotActive);
+            }
+
+            let now = self.env().block_timestamp();
+            if now < proposal.voting_ends_at {
+                return Err(Error::VotingPeriodNotEnded);
+            }
+
+            // Determine outcome
+            let quorum_reached = proposal.quorum_reached();
+
+            if quorum_reached && proposal.votes_for > proposal.votes_against {
+                // Fails, leaving the proposal to be queued again later, if the
+                // treasury can no longer cover the budget
+                if let (Some(treasury), Some(funding)) = (self.treasury, &proposal.funding) {
+                    let mut treasury: contract_ref!(Treasury) = treasury.into();
+                    treasury
+                        .reserve(proposal_id, funding.asset, funding.beneficiary, funding.tranches(proposal.budget))
+                        .map_err(|_| Error::BudgetExceedsTreasury)?;
+                }
+
+                self.settle_deposit(&mut proposal, true);
+                proposal.status = ProposalStatus::Passed;
+                proposal.executable_at = now + self.timing.timelock_delay;
+                proposal.expires_at = proposal.executable_at + self.timing.execution_window;
+                self.proposals.insert(proposal_id, &proposal);
+
+                self.env().emit_event(ProposalQueued {
+                    proposal_id,
+                    executable_at: proposal.executable_at,
+                    expires_at: proposal.expires_at,
+                });
+            } else {
+                self.settle_deposit(&mut proposal, quorum_reached);
+                proposal.status = ProposalStatus::Rejected;
+                self.proposals.insert(proposal_id, &proposal);
+
+                self.env().emit_event(ProposalExecuted {
+                    proposal_id,
+                    status: proposal.status.clone(),
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Runs the calls of a queued proposal once its timelock has elapsed.
+        /// A proposal left unexecuted past its window is marked `Expired`.
+        #[ink(message)]
+        pub fn execute_proposal(&mut self, proposal_id: u32) -> Result<()> {
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+
+            if proposal.status != ProposalStatus::Passed {
+                return Err(Error::ProposalNotQueued);
+            }
+
+            let now = self.env().block_timestamp();
+            if now < proposal.executable_at {
+                return Err(Error::TimelockNotElapsed);
+            }
+
+            if now >= proposal.expires_at {
+                proposal.status = ProposalStatus::Expired;
+                self.proposals.insert(proposal_id, &proposal);
+            } else {
+                // Persist first so a re-entrant call cannot execute it again
+                proposal.status = ProposalStatus::Executed;
+                self.proposals.insert(proposal_id, &proposal);
+                // Successful calls may have rewritten the proposal, e.g. in a
+                // `migrate`, so it is only written again when they reverted
+                if let Err((call_index, reason)) = self.dispatch_atomically(&proposal) {
+                    proposal.status = ProposalStatus::ExecutionFailed { call_index, reason };
+                    self.proposals.insert(proposal_id, &proposal);
+                }
+            }
+
+            if proposal.status != ProposalStatus::Executed {
+                self.cancel_reservation(&proposal);
+            }
+
+            self.env().emit_event(ProposalExecuted {
+                proposal_id,
+                status: proposal.status.clone(),
+            });
+
+            Ok(())
+        }
+
+        /// Runs the calls of the proposal being executed and starts its
+        /// payout. Only callable by this contract from `execute_proposal`; a
+        /// failing call makes it return `Err`, which reverts the calls that
+        /// went through before it.
+        #[ink(message)]
+        pub fn run_proposal(&mut self, proposal_id: u32) -> Result<()> {
+            self.ensure_governance()?;
+            if self.executing.get().flatten() != Some(proposal_id) {
+                return Err(Error::Unauthorized);
+            }
+            // Proposal calls cannot run it a second time
+            self.executing.set(&None);
+
+            let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            self.dispatch_calls(&proposal.calls)
+                .and_then(|()| self.start_payout(&proposal))
+                .map_err(|(call_index, reason)| Error::CallFailed { call_index, reason })
+        }
+
+        #[ink(message)]
+        pub fn get_proposal(&self, proposal_id: u32) -> Option<Proposal> {
+            self.proposals.get(proposal_id)
+        }
+
+        /// Number of proposals created so far; IDs run from 0 to this minus one.
+        #[ink(message)]
+        pub fn proposal_count(&self) -> u32 {
+            self.proposal_count
+        }
+
+        /// Proposals with IDs from `offset` to `offset + limit`, keeping only
+        /// those whose status is of the same kind as `status_filter`. At most
+        /// `MAX_PAGE_SIZE` IDs are scanned, so a filtered page may hold fewer
+        /// entries than it covers.
+        #[ink(message)]
+        pub fn list_proposals(&self, offset: u32, limit: u32, status_filter: Option<ProposalStatus>) -> Vec<Proposal> {
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(self.proposal_count);
+            (offset..end)
+                .filter_map(|proposal_id| self.proposals.get(proposal_id))
+                .filter(|proposal| {
+                    status_filter
+                        .as_ref()
+                        .is_none_or(|status| core::mem::discriminant(status) == core::mem::discriminant(&proposal.status))
+                })
+                .collect()
+        }
+
+        /// Number of proposals `proposer` has created, revisions included.
+        #[ink(message)]
+        pub fn proposals_by_proposer_count(&self, proposer: AccountId) -> u32 {
+            self.proposals_of_counts.get(proposer).unwrap_or(0)
+        }
+
+        /// IDs of the proposals `proposer` created, oldest first.
+        #[ink(message)]
+        pub fn proposals_by_proposer(&self, proposer: AccountId, offset: u32, limit: u32) -> Vec<u32> {
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(self.proposals_by_proposer_count(proposer));
+            (offset..end).filter_map(|index| self.proposals_of.get((proposer, index))).collect()
+        }
+
+        #[ink(message)]
+        pub fn get_vote(&self, proposal_id: u32, voter: AccountId) -> Option<VoteRecord> {
+            let vote = self.votes.get((proposal_id, voter))?;
+            Some(VoteRecord {
+                vote,
+                voting_power: self.vote_weights.get((proposal_id, voter)).unwrap_or(0),
+            })
+        }
+
+        #[ink(message)]
+        pub fn get_timing(&self) -> ProposalTiming {
+            self.timing.clone()
+        }
+
+        /// Updates the proposal lifecycle durations. Only reachable through
+        /// an executed proposal; applies to proposals created or queued later.
+        #[ink(message)]
+        pub fn set_timing(&mut self, timing: ProposalTiming) -> Result<()> {
+            self.ensure_governance()?;
+            if !Self::is_valid_timing(&timing) {
+                return Err(Error::InvalidTiming);
+            }
+
+            self.timing = timing.clone();
+            self.env().emit_event(TimingUpdated { timing });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_treasury(&self) -> Option<AccountId> {
+            self.treasury
+        }
+
+        /// Points budget payouts at a treasury contract. A treasurer may wire
+        /// the first treasury; replacing it takes an executed proposal.
+        #[ink(message)]
+        pub fn set_treasury(&mut self, treasury: Option<AccountId>) -> Result<()> {
+            if !(self.treasury.is_none() && self.access.has_role(TREASURER, self.env().caller())) {
+                self.ensure_governance()?;
+            }
+            self.treasury = treasury;
+            self.env().emit_event(TreasuryUpdated { treasury });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_citizen_registry(&self) -> Option<AccountId> {
+            self.citizen_registry
+        }
+
+        /// Points voter checks at a citizen registry. The owner may wire the
+        /// first registry; replacing or removing it takes an executed
+        /// proposal.
+        #[ink(message)]
+        pub fn set_citizen_registry(&mut self, citizen_registry: Option<AccountId>) -> Result<()> {
+            if !(self.citizen_registry.is_none() && self.env().caller() == self.access.owner()) {
+                self.ensure_governance()?;
+            }
+            self.citizen_registry = citizen_registry;
+            self.env().emit_event(CitizenRegistryUpdated { citizen_registry });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_zone_registry(&self) -> Option<AccountId> {
+            self.zone_registry.get().flatten()
+        }
+
+        /// Points zone-local proposals at a zone registry. The owner may wire
+        /// the first registry; replacing or removing it takes an executed
+        /// proposal.
+        #[ink(message)]
+        pub fn set_zone_registry(&mut self, zone_registry: Option<AccountId>) -> Result<()> {
+            if !(self.get_zone_registry().is_none() && self.env().caller() == self.access.owner()) {
+                self.ensure_governance()?;
+            }
+            self.zone_registry.set(&zone_registry);
+            self.env().emit_event(ZoneRegistryUpdated { zone_registry });
+            Ok(())
+        }
+
+        /// Replaces this contract's code, keeping its storage. Only reachable
+        /// through an executed proposal.
+        /// Follow it with `migrate` in the same proposal.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<()> {
+            self.ensure_governance()?;
+            self.env().set_code_hash(&code_hash).map_err(|_| Error::UpgradeFailed)?;
+            self.env().emit_event(CodeUpgraded { code_hash });
+            Ok(())
+        }
+
+        /// Brings storage written by an older version up to
+        /// `STORAGE_VERSION`, converting up to `limit` stored proposals from
+        /// `offset` on. Batches must run in order, each starting where the
+        /// last one stopped; `get_migration_cursor` tells where that is. The
+        /// version moves on only after the last batch. Governance starts a
+        /// migration through an executed proposal; once started, anyone may
+        /// run the remaining batches, since they only re-encode stored
+        /// entries.
+        #[ink(message)]
+        pub fn migrate(&mut self, offset: u32, limit: u32) -> Result<()> {
+            let cursor = match self.migration_cursor.get().flatten() {
+                Some(cursor) => cursor,
+                None => {
+                    self.ensure_governance()?;
+                    0
+                }
+            };
+            let from = self.storage_version.get().unwrap_or(STORAGE_VERSION);
+            if from > STORAGE_VERSION {
+                return Err(Error::UnsupportedStorageVersion);
+            }
+            if offset != cursor {
+                return Err(Error::MigrationOutOfOrder);
+            }
+            let end = offset.saturating_add(limit).min(self.proposal_count);
+            // Steps for later versions go here, oldest first
+            if from < 2 {
+                // Version 2 scoped proposals to zones
+                let proposals = self.proposals.key();
+                for proposal_id in offset..end {
+                    let stored = ink::env::get_contract_storage::<_, ProposalV1>(&(proposals, proposal_id));
+                    if let Ok(Some(proposal)) = stored {
+                        self.proposals.insert(proposal_id, &Proposal::from(proposal));
+                    }
+                }
+            }
+            if from < STORAGE_VERSION && end < self.proposal_count {
+                self.migration_cursor.set(&Some(end));
+                return Ok(());
+            }
+            self.migration_cursor.set(&None);
+            self.storage_version.set(&STORAGE_VERSION);
+            self.env().emit_event(StorageMigrated { from, to: STORAGE_VERSION });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or(STORAGE_VERSION)
+        }
+
+        /// Where the next `migrate` batch starts, while a migration runs.
+        #[ink(message)]
+        pub fn get_migration_cursor(&self) -> Option<u32> {
+            self.migration_cursor.get().flatten()
+        }
+
+        #[ink(message)]
+        pub fn get_guardian(&self) -> Option<AccountId> {
+            self.guardian
+        }
+
+        /// Sets or clears the guardian. Only reachable through an executed
+        /// proposal.
+        #[ink(message)]
+        pub fn set_guardian(&mut self, guardian: Option<AccountId>) -> Result<()> {
+            self.ensure_governance()?;
+            self.guardian = guardian;
+            self.env().emit_event(GuardianUpdated { guardian });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_voting_token(&self) -> Option<AccountId> {
+            self.voting_token
+        }
+
+        /// Switches between token-weighted (`Some`) and reputation-weighted
+        /// (`None`) voting for proposals created afterwards. The token must
+        /// implement `PSP22Votes`. Only reachable through an executed
+        /// proposal.
+        #[ink(message)]
+        pub fn set_voting_token(&mut self, voting_token: Option<AccountId>) -> Result<()> {
+            self.ensure_governance()?;
+            self.voting_token = voting_token;
+            self.env().emit_event(VotingTokenUpdated { voting_token });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_citizen_profile(&self, citizen: AccountId) -> CitizenProfile {
+            self.citizen_profiles.get(citizen).unwrap_or_default()
+        }
+
+        /// Reputation of `citizen` after decay up to now.
+        #[ink(message)]
+        pub fn get_effective_reputation(&self, citizen: AccountId) -> u32 {
+            self.decayed_profile(citizen).reputation_score
+        }
+
+        #[ink(message)]
+        pub fn get_proposal_requirements(&self) -> ProposalRequirements {
+            self.requirements.clone()
+        }
+
+        /// Deposits refunded to `proposer` and not yet claimed.
+        #[ink(message)]
+        pub fn get_claimable_deposit(&self, proposer: AccountId) -> Balance {
+            self.deposit_refunds.get(proposer).unwrap_or(0)
+        }
+
+        /// Pays out the caller's refunded deposits. Refunds are pulled rather
+        /// than pushed so that a proposer who cannot receive them does not
+        /// block tallying.
+        #[ink(message)]
+        pub fn claim_deposit(&mut self) -> Result<Balance> {
+            let proposer = self.env().caller();
+            let amount = self.deposit_refunds.take(proposer).unwrap_or(0);
+            if amount > 0 {
+                self.env().transfer(proposer, amount).map_err(|_| Error::DepositTransferFailed)?;
+                self.env().emit_event(DepositClaimed { proposer, amount });
+            }
+            Ok(amount)
+        }
+
+        /// Changes the proposal threshold and deposit for proposals created
+        /// afterwards. Only reachable through an executed proposal.
+        #[ink(message)]
+        pub fn set_proposal_requirements(&mut self, requirements: ProposalRequirements) -> Result<()> {
+            self.ensure_governance()?;
+            self.requirements = requirements.clone();
+            self.env().emit_event(ProposalRequirementsUpdated { requirements });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_reputation_decay(&self) -> ReputationDecay {
+            self.reputation_decay.clone()
+        }
+
+        /// Changes how reputation decays. The new curve also applies to time
+        /// already spent inactive. Only reachable through an executed proposal.
+        #[ink(message)]
+        pub fn set_reputation_decay(&mut self, decay: ReputationDecay) -> Result<()> {
+            self.ensure_governance()?;
+            if !decay.is_valid() {
+                return Err(Error::InvalidDecay);
+            }
+            self.reputation_decay = decay.clone();
+            self.env().emit_event(ReputationDecayUpdated { decay });
+            Ok(())
+        }
+
+        /// Reputation voting power `citizen` held before `block`.
+        #[ink(message)]
+        pub fn voting_power_at(&self, citizen: AccountId, block: BlockNumber) -> u32 {
+            // Binary search for the last checkpoint strictly before `block`
+            let (mut low, mut high) = (0, self.checkpoint_counts.get(citizen).unwrap_or(0));
+            while low < high {
+                let mid = low + (high - low) / 2;
+                match self.checkpoints.get((citizen, mid)) {
+                    Some(checkpoint) if checkpoint.block < block => low = mid + 1,
+                    _ => high = mid,
+                }
+            }
+
+            if low == 0 {
+                return CitizenProfile::default().voting_power;
+            }
+            self.checkpoints
+                .get((citizen, low - 1))
+                .map(|checkpoint| checkpoint.voting_power)
+                .unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn get_checkpoint(&self, citizen: AccountId, index: u32) -> Option<Checkpoint> {
+            self.checkpoints.get((citizen, index))
+        }
+
+        #[ink(message)]
+        pub fn get_checkpoint_count(&self, citizen: AccountId) -> u32 {
+            self.checkpoint_counts.get(citizen).unwrap_or(0)
+        }
+
+        /// Stores the AI oracle's analysis of a proposal. Only the registered
+        /// oracle may answer, once per proposal, while voting is open.
+        #[ink(message)]
+        pub fn submit_ai_analysis(
+            &mut self,
+            proposal_id: u32,
+            score: u32,
+            risk_level: RiskLevel,
+            summary_hash: Hash,
+        ) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("submit_ai_analysis"))?;
+            if self.env().caller() != self.ai_oracle {
+                return Err(Error::Unauthorized);
+            }
+            if score > 100 {
+                return Err(Error::InvalidScore);
+            }
+
+            let mut proposal = self.open_proposal(proposal_id)?;
+            if proposal.ai_recommendation.is_some() {
+                return Err(Error::AnalysisAlreadySubmitted);
+            }
+
+            proposal.ai_recommendation = Some(AiRecommendation {
+                score,
+                risk_level,
+                summary_hash,
+                submitted_at: self.env().block_timestamp(),
+            });
+            self.proposals.insert(proposal_id, &proposal);
+
+            self.env().emit_event(AnalysisSubmitted {
+                proposal_id,
+                score,
+                risk_level,
+                summary_hash,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_ai_oracle(&self) -> AccountId {
+            self.ai_oracle
+        }
+
+        /// Replaces the AI oracle account. Only reachable through an executed
+        /// proposal.
+        #[ink(message)]
+        pub fn set_ai_oracle(&mut self, ai_oracle: AccountId) -> Result<()> {
+            self.ensure_governance()?;
+            self.ai_oracle = ai_oracle;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_ai_contribution(&mut self, citizen: AccountId, contribution_points: u32) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("update_ai_contribution"))?;
+            if self.env().caller() != self.ai_oracle {
+                return Err(Error::Unauthorized);
+            }
+
+            self.update_profile(citizen, |profile| {
+                profile.ai_contributions = profile.ai_contributions.saturating_add(contribution_points);
+                let reward = contribution_points.saturating_mul(5); // 5x multiplier for AI contributions
+                profile.reputation_score = profile.reputation_score.saturating_add(reward);
+            });
+            Ok(())
+        }
+
+        /// Stores a new active proposal and requests its AI analysis.
+        #[allow(clippy::too_many_arguments)]
+        fn insert_proposal(
+            &mut self,
+            proposer: AccountId,
+            title: String,
+            description: String,
+            budget: u128,
+            deposit: Balance,
+            funding: Option<Funding>,
+            calls: Vec<ProposalCall>,
+            voting_scheme: VotingScheme,
+            zone: Option<ZoneId>,
+            revision_of: Option<u32>,
+        ) -> Result<u32> {
+            let proposal_id = self.proposal_count;
+            let voting_supply = match zone {
+                Some(zone) => self.zone_directory()?.population(zone).ok_or(Error::ZoneNotFound)? as u128,
+                None => self.voting_supply(),
+            };
+
+            let proposal = Proposal {
+                id: proposal_id,
+                title: title.clone(),
+                description,
+                proposer,
+                votes_for: 0,
+                votes_against: 0,
+                votes_abstain: 0,
+                status: ProposalStatus::Active,
+                voting_scheme,
+                voting_ends_at: self.env().block_timestamp() + self.timing.voting_period,
+                executable_at: 0,
+                expires_at: 0,
+                budget,
+                deposit,
+                funding,
+                ai_recommendation: None,
+                calls,
+                voting_token: self.voting_token,
+                voting_supply,
+                zone,
+                voter_count: 0,
+                revision_of,
+                superseded_by: None,
+                snapshot_block: self.env().block_number(),
+            };
+
+            self.proposals.insert(proposal_id, &proposal);
+            self.proposal_count += 1;
+            let index = self.proposals_of_counts.get(proposer).unwrap_or(0);
+            self.proposals_of.insert((proposer, index), &proposal_id);
+            self.proposals_of_counts.insert(proposer, &(index + 1));
+
+            self.env().emit_event(ProposalCreated {
+                proposal_id,
+                proposer,
+                title,
+            });
+            self.env().emit_event(AnalysisRequested {
+                proposal_id,
+                oracle: self.ai_oracle,
+            });
+
+            Ok(proposal_id)
+        }
+
+        /// Whether `account` counts as a citizen on `proposal`. The credential
+        /// has to predate the proposal, so an identity moved to a fresh account
+        /// while voting is open cannot vote twice.
+        fn is_citizen(&self, proposal: &Proposal, account: AccountId) -> bool {
+            match self.citizen_registry {
+                Some(registry) => {
+                    let registry: contract_ref!(CitizenRegistry) = registry.into();
+                    registry.citizen_since(account).is_some_and(|since| since < proposal.snapshot_block)
+                }
+                None => self.open_voting.get().unwrap_or(true),
+            }
+        }
+
+        /// Whether `account` may vote on `proposal` as far as zones go. Residency
+        /// is read as it stood before the proposal, so moving in afterwards
+        /// does not add a vote.
+        fn is_resident(&self, proposal: &Proposal, account: AccountId) -> bool {
+            match (proposal.zone, self.zone_directory()) {
+                (Some(zone), Ok(directory)) => directory.zone_of_at(account, proposal.snapshot_block) == Some(zone),
+                (Some(_), Err(_)) => false,
+                (None, _) => true,
+            }
+        }
+
+        fn zone_directory(&self) -> Result<contract_ref!(ZoneDirectory)> {
+            let registry = self.get_zone_registry().ok_or(Error::ZoneRegistryNotSet)?;
+            Ok(registry.into())
+        }
+
+        /// What `citizen` is measured by against the proposal threshold.
+        fn proposer_standing(&self, citizen: AccountId) -> u128 {
+            match self.voting_token {
+                // Balances from earlier blocks, so tokens cannot be borrowed
+                // for the transaction
+                Some(token) => {
+                    let token: contract_ref!(PSP22Votes) = token.into();
+                    token.balance_of_at(citizen, self.env().block_number())
+                }
+                None => self.decayed_profile(citizen).reputation_score as u128,
+            }
+        }
+
+        /// Credits the deposit of a tallied proposal to its proposer, to be
+        /// collected with `claim_deposit`, or slashes it to the treasury.
+        /// Without a treasury, or if the treasury refuses the transfer, a
+        /// slashed deposit stays with this contract.
+        fn settle_deposit(&mut self, proposal: &mut Proposal, refund: bool) {
+            let amount = core::mem::take(&mut proposal.deposit);
+            if amount == 0 {
+                return;
+            }
+
+            if refund {
+                let owed = self.deposit_refunds.get(proposal.proposer).unwrap_or(0);
+                self.deposit_refunds.insert(proposal.proposer, &(owed + amount));
+            } else if let Some(treasury) = self.treasury {
+                let _ = self.env().transfer(treasury, amount);
+            }
+
+            self.env().emit_event(DepositSettled {
+                proposal_id: proposal.id,
+                amount,
+                refunded: refund,
+            });
+        }
+
+        /// Rejects budgets the treasury cannot cover from unreserved funds.
+        fn ensure_affordable(&self, budget: u128, funding: Option<&Funding>) -> Result<()> {
+            if let Some(funding) = funding {
+                if !funding.milestones.is_empty() && funding.milestones.iter().sum::<Balance>() != budget {
+                    return Err(Error::InvalidFunding);
+                }
+                if let Some(vesting) = &funding.vesting {
+                    let streamable = funding.milestones.is_empty() && budget > 0;
+                    if !streamable || vesting.duration == 0 || vesting.cliff > vesting.duration {
+                        return Err(Error::InvalidFunding);
+                    }
+                }
+            }
+            if budget == 0 {
+                return Ok(());
+            }
+
+            let Some(treasury) = self.treasury else {
+                return match funding {
+                    Some(_) => Err(Error::TreasuryNotSet),
+                    None => Ok(()),
+                };
+            };
+            let treasury: contract_ref!(Treasury) = treasury.into();
+            let asset = funding.map(|funding| funding.asset).unwrap_or(Asset::Native);
+            if budget > treasury.available(asset) {
+                return Err(Error::BudgetExceedsTreasury);
+            }
+            Ok(())
+        }
+
+        /// Pays the first milestone of an executed proposal, or starts streaming
+        /// its budget. A failure is reported as one more call after the
+        /// proposal's own calls.
+        fn start_payout(&self, proposal: &Proposal) -> core::result::Result<(), (u32, CallFailure)> {
+            let (Some(treasury), Some(funding)) = (self.treasury, &proposal.funding) else {
+                return Ok(());
+            };
+            let mut treasury: contract_ref!(Treasury) = treasury.into();
+            let result = match &funding.vesting {
+                Some(vesting) => {
+                    let start = self.env().block_timestamp();
+                    treasury.stream_reservation(proposal.id, start, start + vesting.cliff, start + vesting.duration)
+                }
+                None => treasury.release_next_tranche(proposal.id),
+            };
+            result
+                .map(|_| ())
+                .map_err(|error| (proposal.calls.len() as u32, CallFailure::Reverted(scale::Encode::encode(&error))))
+        }
+
+        /// Frees the treasury reservation of a proposal that will not execute.
+        fn cancel_reservation(&self, proposal: &Proposal) {
+            if let (Some(treasury), Some(_)) = (self.treasury, &proposal.funding) {
+                let mut treasury: contract_ref!(Treasury) = treasury.into();
+                // Nothing was reserved if the treasury changed since queueing
+                let _ = treasury.cancel_reservation(proposal.id);
+            }
+        }
+
+        /// Loads a proposal that is still accepting votes.
+        fn open_proposal(&self, proposal_id: u32) -> Result<Proposal> {
+            let proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if proposal.status != ProposalStatus::Active {
+                return Err(Error::ProposalNotActive);
+            }
+            if self.env().block_timestamp() >= proposal.voting_ends_at {
+                return Err(Error::VotingPeriodEnded);
+            }
+            Ok(proposal)
+        }
+
+        /// Governance-only settings are changed by the contract calling itself
+        /// from an executed proposal.
+        fn ensure_governance(&self) -> Result<()> {
+            if self.env().caller() != self.env().account_id() {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+
+        /// Records a first vote of `voting_power`, carrying the power of
+        /// `heads` citizens, and rewards the voter.
+        fn record_vote(
+            &mut self,
+            proposal: &mut Proposal,
+            voter: AccountId,
+            vote: VoteType,
+            voting_power: u128,
+            heads: u32,
+        ) {
+            let proposal_id = proposal.id;
+            self.votes.insert((proposal_id, voter), &vote);
+            self.vote_weights.insert((proposal_id, voter), &voting_power);
+            self.vote_heads.insert((proposal_id, voter), &heads);
+
+            // Update proposal vote counts
+            *proposal.tally_mut(vote) += voting_power;
+            proposal.voter_count += heads;
+            self.proposals.insert(proposal_id, proposal);
+
+            // Update citizen profile, rewarding only the first vote on a proposal
+            if !self.withdrawals.contains((proposal_id, voter)) {
+                self.update_profile(voter, |profile| {
+                    profile.votes_cast += 1;
+                    profile.reputation_score = profile.reputation_score.saturating_add(25); // Reward for voting
+                });
+            }
+
+            self.env().emit_event(VoteCast {
+                proposal_id,
+                voter,
+                vote,
+                voting_power,
+            });
+        }
+
+        /// Credits spent by `citizen` in the current epoch; a usage record
+        /// from an earlier epoch counts as nothing spent.
+        fn current_credit_usage(&self, citizen: AccountId) -> CreditUsage {
+            let epoch = self.env().block_timestamp() / self.voice_credits.epoch_length;
+            match self.credit_usage.get(citizen) {
+                Some(usage) if usage.epoch == epoch => usage,
+                _ => CreditUsage { epoch, spent: 0 },
+            }
+        }
+
+        /// Own voting power of `citizen` on `proposal`, excluding delegations.
+        /// Accounts without a citizenship credential from before the proposal,
+        /// or living outside the zone of a zone-local proposal, have none.
+        fn voting_power_of(&self, proposal: &Proposal, citizen: AccountId) -> u128 {
+            if !self.is_citizen(proposal, citizen) || !self.is_resident(proposal, citizen) {
+                return 0;
+            }
+            match proposal.voting_token {
+                // Tokens moved after the snapshot cannot be voted again
+                Some(token) => {
+                    let token: contract_ref!(PSP22Votes) = token.into();
+                    token.balance_of_at(citizen, proposal.snapshot_block)
+                }
+                // Decay since the snapshot can only lower it, never raise it
+                None => {
+                    let decayed = self.decayed_profile(citizen).voting_power;
+                    self.voting_power_at(citizen, proposal.snapshot_block).min(decayed) as u128
+                }
+            }
+        }
+
+        /// Sums the power `voter` casts on `proposal`: their own plus that of
+        /// every delegator down the chain who has not voted directly. Power a
+        /// delegator had already lent to another caster is taken back from it.
+        /// Also returns how many citizens the power came from.
+        fn collect_voting_power(&mut self, proposal: &mut Proposal, voter: AccountId) -> (u128, u32) {
+            let round = self.withdrawals.get((proposal.id, voter)).unwrap_or(0);
+            let (mut total, mut heads) = (0, 0);
+            let mut pending = ink::prelude::vec![(voter, 0)];
+            while let Some((citizen, depth)) = pending.pop() {
+                if citizen != voter && self.votes.contains((proposal.id, citizen)) {
+                    continue; // voted directly, together with their own delegators
+                }
+
+                if let Some(cast) = self.cast_power.get((proposal.id, citizen)) {
+                    if cast.caster == voter && cast.round == round {
+                        continue; // reached twice, but counted once
+                    }
+                    self.revoke_cast_power(proposal, citizen, cast);
+                }
+                let power = self.voting_power_of(proposal, citizen);
+                self.cast_power.insert((proposal.id, citizen), &CastPower { caster: voter, power, round });
+                total += power;
+                if power > 0 {
+                    heads += 1;
+                }
+
+                if depth < MAX_DELEGATION_DEPTH {
+                    for delegator in self.delegators.get(citizen).unwrap_or_default() {
+                        pending.push((delegator, depth + 1));
+                    }
+                }
+            }
+            (total, heads)
+        }
+
+        /// Removes the power `delegator` lent from the vote it was cast in.
+        fn revoke_cast_power(&mut self, proposal: &mut Proposal, delegator: AccountId, cast: CastPower) {
+            let CastPower { caster, power, round } = cast;
+            if self.withdrawals.get((proposal.id, caster)).unwrap_or(0) != round {
+                return; // withdrawn together with the caster's vote
+            }
+            let Some(vote) = self.votes.get((proposal.id, caster)) else {
+                return;
+            };
+            *proposal.tally_mut(vote) -= power;
+            let weight = self.vote_weights.get((proposal.id, caster)).unwrap_or(0);
+            self.vote_weights.insert((proposal.id, caster), &(weight - power));
+            if power > 0 {
+                let heads = self.vote_heads.get((proposal.id, caster)).unwrap_or(1);
+                self.vote_heads.insert((proposal.id, caster), &heads.saturating_sub(1));
+                proposal.voter_count = proposal.voter_count.saturating_sub(1);
+            }
+
+            self.env().emit_event(DelegatedVoteOverridden {
+                proposal_id: proposal.id,
+                delegate: caster,
+                delegator,
+                voting_power: power,
+            });
+        }
+
+        /// Length of the longest delegation chain ending at `citizen`.
+        fn delegation_height(&self, citizen: AccountId, depth: u32) -> u32 {
+            if depth >= MAX_DELEGATION_DEPTH {
+                return 0;
+            }
+            self.delegators
+                .get(citizen)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|delegator| 1 + self.delegation_height(delegator, depth + 1))
+                .max()
+                .unwrap_or(0)
+        }
+
+        fn remove_delegator(&mut self, delegate: AccountId, delegator: AccountId) {
+            let mut delegators = self.delegators.get(delegate).unwrap_or_default();
+            delegators.retain(|account| *account != delegator);
+            self.delegators.insert(delegate, &delegators);
+        }
+
+        /// Profile of `citizen` with reputation decayed up to now and voting
+        /// power recomputed from it. Citizens without a profile start fresh.
+        fn decayed_profile(&self, citizen: AccountId) -> CitizenProfile {
+            let now = self.env().block_timestamp();
+            let Some(mut profile) = self.citizen_profiles.get(citizen) else {
+                return CitizenProfile { last_active: now, ..Default::default() };
+            };
+            profile.reputation_score =
+                self.reputation_decay.apply(profile.reputation_score, now.saturating_sub(profile.last_active));
+            profile.voting_power = (profile.reputation_score / 100).max(1);
+            profile
+        }
+
+        /// Applies a reward to the decayed profile of `citizen`, marks them
+        /// active and checkpoints the resulting voting power.
+        fn update_profile(&mut self, citizen: AccountId, update: impl FnOnce(&mut CitizenProfile)) {
+            let mut profile = self.decayed_profile(citizen);
+            update(&mut profile);
+            profile.last_active = self.env().block_timestamp();
+            profile.voting_power = (profile.reputation_score / 100).max(1);
+
+            self.citizen_profiles.insert(citizen, &profile);
+            self.write_checkpoint(citizen, profile.voting_power);
+        }
+
+        /// Records `voting_power` as the citizen's power from the current block
+        /// on, overwriting a checkpoint already written in this block.
+        fn write_checkpoint(&mut self, citizen: AccountId, voting_power: u32) {
+            let block = self.env().block_number();
+            let count = self.checkpoint_counts.get(citizen).unwrap_or(0);
+
+            if count > 0 {
+                if let Some(last) = self.checkpoints.get((citizen, count - 1)) {
+                    if last.block == block {
+                        self.checkpoints.insert((citizen, count - 1), &Checkpoint { block, voting_power });
+                        return;
+                    }
+                }
+            }
+
+            self.checkpoints.insert((citizen, count), &Checkpoint { block, voting_power });
+            self.checkpoint_counts.insert(citizen, &(count + 1));
+        }
+
+        /// Supply the quorum of a new proposal is measured against: the
+        /// token's supply at the snapshot in token mode, the configured
+        /// constant otherwise.
+        fn voting_supply(&self) -> u128 {
+            match self.voting_token {
+                Some(token) => {
+                    let token: contract_ref!(PSP22Votes) = token.into();
+                    token.total_supply_at(self.env().block_number())
+                }
+                None => self.total_supply,
+            }
+        }
+
+        fn is_valid_timing(timing: &ProposalTiming) -> bool {
+            timing.voting_period > 0 && timing.execution_window > 0
+        }
+
+        /// Runs the calls and payout of a proposal in a nested `run_proposal`
+        /// call, so they take effect all together or not at all. A failure
+        /// is reported with the index of the call that caused it.
+        fn dispatch_atomically(&mut self, proposal: &Proposal) -> core::result::Result<(), (u32, CallFailure)> {
+            // Nothing to run means nothing to revert
+            if proposal.calls.is_empty() && proposal.funding.is_none() {
+                return Ok(());
+            }
+            let run = ProposalCall {
+                callee: self.env().account_id(),
+                selector: ink::selector_bytes!("run_proposal"),
+                input: scale::Encode::encode(&proposal.id),
+                transferred_value: 0,
+            };
+            self.executing.set(&Some(proposal.id));
+            let result = self.dispatch_calls(&[run]);
+            self.executing.set(&None);
+
+            result.map_err(|(_, reason)| Self::failed_call(reason))
+        }
+
+        /// The proposal call behind a failed `run_proposal`, read from the
+        /// `CallFailed` error it reverted with.
+        fn failed_call(reason: CallFailure) -> (u32, CallFailure) {
+            match reason {
+                CallFailure::Reverted(error) => match scale::Decode::decode(&mut &error[..]) {
+                    Ok(Error::CallFailed { call_index, reason }) => (call_index, reason),
+                    _ => (0, CallFailure::Reverted(error)),
+                },
+                reason => (0, reason),
+            }
+        }
+
+        /// Runs the calls in order and stops at the first one that fails.
+        ///
+        /// Calls that already went through are only rolled back by the
+        /// caller reverting, as `run_proposal` does. A call that targets this
+        /// contract may re-enter it, so storage is flushed before and
+        /// reloaded after such a call.
+        fn dispatch_calls(&mut self, calls: &[ProposalCall]) -> core::result::Result<(), (u32, CallFailure)> {
+            let own_account = self.env().account_id();
+            for (index, call) in calls.iter().enumerate() {
+                let reentrant = call.callee == own_account;
+                if reentrant {
+                    ink::env::set_contract_storage(&<Self as ink::storage::traits::StorageKey>::KEY, self);
+                }
+
+                let result = invoke(
+                    call.callee,
+                    call.selector,
+                    &call.input,
+                    call.transferred_value,
+                    reentrant,
+                );
+
+                if reentrant {
+                    self.reload_storage().map_err(|reason| (index as u32, reason))?;
+                }
+
+                if let Err(reason) = result {
+                    return Err((index as u32, reason));
+                }
+            }
+            Ok(())
+        }
+
+        /// Reads back the storage a re-entrant call may have changed.
+        fn reload_storage(&mut self) -> core::result::Result<(), CallFailure> {
+            match ink::env::get_contract_storage(&<Self as ink::storage::traits::StorageKey>::KEY) {
+                Ok(Some(reloaded)) => {
+                    *self = reloaded;
+                    Ok(())
+                }
+                // Going on with the stale copy would overwrite what the call wrote
+                _ => Err(CallFailure::StorageNotReloaded),
+            }
+        }
+    }
+
+    impl AccessControlled for CityGovernance {
+        #[ink(message)]
+        fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+            self.access.has_role(role, account)
+        }
+
+        #[ink(message)]
+        fn grant_role(&mut self, role: RoleId, account: AccountId) -> core::result::Result<(), AccessError> {
+            let sender = self.env().caller();
+            if self.access.grant_role(sender, role, account)? {
+                self.env().emit_event(RoleGranted { role, account, sender });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn revoke_role(&mut self, role: RoleId, account: AccountId) -> core::result::Result<(), AccessError> {
+            let sender = self.env().caller();
+            if self.access.revoke_role(sender, role, account)? {
+                self.env().emit_event(RoleRevoked { role, account, sender });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn renounce_role(&mut self, role: RoleId) -> core::result::Result<(), AccessError> {
+            let account = self.env().caller();
+            self.access.renounce_role(account, role)?;
+            self.env().emit_event(RoleRevoked { role, account, sender: account });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn owner(&self) -> AccountId {
+            self.access.owner()
+        }
+
+        #[ink(message)]
+        fn pending_owner(&self) -> Option<AccountId> {
+            self.access.pending_owner()
+        }
+
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> core::result::Result<(), AccessError> {
+            let owner = self.env().caller();
+            self.access.transfer_ownership(owner, new_owner)?;
+            self.env().emit_event(OwnershipTransferStarted {
+                owner,
+                pending_owner: new_owner,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn accept_ownership(&mut self) -> core::result::Result<(), AccessError> {
+            let new_owner = self.env().caller();
+            let previous_owner = self.access.accept_ownership(new_owner)?;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            });
+            Ok(())
+        }
+    }
+
+    impl Pausable for CityGovernance {
+        #[ink(message)]
+        fn is_paused(&self, function: FunctionId) -> bool {
+            self.pauses.is_paused(function)
+        }
+
+        #[ink(message)]
+        fn pause(&mut self, function: FunctionId) -> core::result::Result<(), PauseError> {
+            let account = self.env().caller();
+            if !self.access.has_role(PAUSER, account) {
+                return Err(PauseError::Unauthorized);
+            }
+            if self.pauses.pause(function)? {
+                let until = self.pauses.paused_until(function).unwrap_or_default();
+                self.env().emit_event(Paused { function, account, until });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn unpause(&mut self, function: FunctionId) -> core::result::Result<(), PauseError> {
+            self.ensure_governance().map_err(|_| PauseError::Unauthorized)?;
+            if self.pauses.unpause(function) {
+                self.env().emit_event(Unpaused {
+                    function,
+                    account: self.env().caller(),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    /// Integer square root, rounded down.
+    fn isqrt(value: u128) -> u128 {
+        if value < 2 {
+            return value;
+        }
+        // Newton's method, starting from an over-estimate
+        let mut x = value;
+        let mut y = x.div_ceil(2);
+        while y < x {
+            x = y;
+            y = (x + value / x) / 2;
+        }
+        x
+    }
+
+    impl Proposal {
+        /// Whether a tenth of the voting supply took part. Zone-local
+        /// proposals count the citizens behind the votes against the zone's
+        /// population, since power and headcount are not comparable.
+        fn quorum_reached(&self) -> bool {
+            match self.zone {
+                Some(_) => self.voter_count as u128 * 10 >= self.voting_supply,
+                None => self.votes_for + self.votes_against + self.votes_abstain >= self.voting_supply / 10,
+            }
+        }
+
+        /// The tally votes of type `vote` are counted in.
+        fn tally_mut(&mut self, vote: VoteType) -> &mut u128 {
+            match vote {
+                VoteType::For => &mut self.votes_for,
+                VoteType::Against => &mut self.votes_against,
+                VoteType::Abstain => &mut self.votes_abstain,
+            }
+        }
+    }
+
+    impl Default for CitizenProfile {
+        fn default() -> Self {
+            Self {
+                reputation_score: 100,
+                voting_power: 1,
+                proposals_submitted: 0,
+                votes_cast: 0,
+                ai_contributions: 0,
+                last_active: 0,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use city_common::pausable::{ALL_FUNCTIONS, PAUSE_COOLDOWN, PAUSE_DURATION};
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        type Event = <CityGovernance as ink::reflect::ContractEventBase>::Type;
+
+        const TOTAL_SUPPLY: u128 = 100;
+        const VOTING_PERIOD: u64 = 1_000;
+        const TIMELOCK_DELAY: u64 = 100;
+        const EXECUTION_WINDOW: u64 = 1_000;
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        /// Governance deployed by Alice, with Bob as the AI oracle and Eve as
+        /// the guardian.
+        fn governance() -> CityGovernance {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            CityGovernance::new(
+                TOTAL_SUPPLY,
+                None,
+                accounts.bob,
+                Some(accounts.eve),
+                true,
+                VOTING_PERIOD,
+                TIMELOCK_DELAY,
+                EXECUTION_WINDOW,
+            )
+        }
+
+        /// Gives `citizen` `voting_power` through AI contribution rewards, in
+        /// a block before any proposal opened afterwards. Leaves the oracle
+        /// as the caller.
+        fn grant_voting_power(governance: &mut CityGovernance, citizen: AccountId, voting_power: u32) {
+            set_caller(accounts().bob);
+            let points = (voting_power * 100 - governance.get_effective_reputation(citizen)) / 5;
+            assert_eq!(governance.update_ai_contribution(citizen, points), Ok(()));
+        }
+
+        /// Opens a proposal carrying `calls` as `proposer` in a fresh block.
+        fn propose_calls(governance: &mut CityGovernance, proposer: AccountId, calls: Vec<ProposalCall>) -> u32 {
+            test::advance_block::<DefaultEnvironment>();
+            set_caller(proposer);
+            governance
+                .create_proposal(String::from("Proposal"), String::new(), 0, None, calls, VotingScheme::Linear, None)
+                .expect("proposal is created")
+        }
+
+        /// Opens a proposal without calls as `proposer` in a fresh block.
+        fn propose(governance: &mut CityGovernance, proposer: AccountId) -> u32 {
+            propose_calls(governance, proposer, Vec::new())
+        }
+
+        /// Opens a quadratic proposal as `proposer` in a fresh block.
+        fn propose_quadratic(governance: &mut CityGovernance, proposer: AccountId) -> u32 {
+            test::advance_block::<DefaultEnvironment>();
+            set_caller(proposer);
+            let title = String::from("Proposal");
+            governance
+                .create_proposal(title, String::new(), 0, None, Vec::new(), VotingScheme::Quadratic, None)
+                .expect("proposal is created")
+        }
+
+        fn call(selector: [u8; 4]) -> ProposalCall {
+            ProposalCall {
+                callee: accounts().frank,
+                selector,
+                input: Vec::new(),
+                transferred_value: 0,
+            }
+        }
+
+        /// Moves time to the end of the proposal's voting period.
+        fn end_voting(governance: &CityGovernance, proposal_id: u32) {
+            let voting_ends_at = governance.get_proposal(proposal_id).unwrap().voting_ends_at;
+            test::set_block_timestamp::<DefaultEnvironment>(voting_ends_at);
+        }
+
+        /// Passes a proposal with Charlie's votes and queues it.
+        fn pass(governance: &mut CityGovernance, proposal_id: u32) {
+            set_caller(accounts().charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            end_voting(governance, proposal_id);
+            assert_eq!(governance.queue_proposal(proposal_id), Ok(()));
+        }
+
+        fn executed_with(proposal_id: u32, expected: ProposalStatus) -> bool {
+            test::recorded_events()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .any(|event| {
+                    matches!(event, Event::ProposalExecuted(ProposalExecuted { proposal_id: id, status })
+                        if id == proposal_id && status == expected)
+                })
+        }
+
+        #[ink::test]
+        fn passed_proposals_without_calls_are_executed() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let proposal_id = propose(&mut governance, accounts.alice);
+            pass(&mut governance, proposal_id);
+
+            let executable_at = governance.get_proposal(proposal_id).unwrap().executable_at;
+            test::set_block_timestamp::<DefaultEnvironment>(executable_at);
+            assert_eq!(governance.execute_proposal(proposal_id), Ok(()));
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().status, ProposalStatus::Executed);
+            assert_eq!(governance.execute_proposal(proposal_id), Err(Error::ProposalNotQueued));
+            assert!(executed_with(proposal_id, ProposalStatus::Executed));
+        }
+
+        #[ink::test]
+        fn proposals_carry_a_bounded_number_of_calls() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let calls = (0..=MAX_PROPOSAL_CALLS as u8).map(|index| call([index; 4])).collect::<Vec<_>>();
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                governance.create_proposal(
+                    String::from("Proposal"),
+                    String::new(),
+                    0,
+                    None,
+                    calls.clone(),
+                    VotingScheme::Linear,
+                    None
+                ),
+                Err(Error::TooManyCalls)
+            );
+
+            let allowed = calls[..MAX_PROPOSAL_CALLS as usize].to_vec();
+            let proposal_id = propose_calls(&mut governance, accounts.alice, allowed.clone());
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().calls, allowed);
+        }
+
+        #[ink::test]
+        fn calls_to_plain_accounts_fail_the_execution() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let proposal_id = propose_calls(&mut governance, accounts.alice, vec![call([1; 4])]);
+            pass(&mut governance, proposal_id);
+
+            let executable_at = governance.get_proposal(proposal_id).unwrap().executable_at;
+            test::set_block_timestamp::<DefaultEnvironment>(executable_at);
+            assert_eq!(governance.execute_proposal(proposal_id), Ok(()));
+            let failed = ProposalStatus::ExecutionFailed {
+                call_index: 0,
+                reason: CallFailure::NotCallable,
+            };
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().status, failed);
+            assert!(executed_with(proposal_id, failed));
+        }
+
+        #[ink::test]
+        fn votes_are_tallied_only_after_the_voting_period() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let proposal_id = propose(&mut governance, accounts.alice);
+            let voting_ends_at = governance.get_proposal(proposal_id).unwrap().voting_ends_at;
+            let created_at = ink::env::block_timestamp::<DefaultEnvironment>();
+            assert_eq!(voting_ends_at, created_at + VOTING_PERIOD);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            test::set_block_timestamp::<DefaultEnvironment>(voting_ends_at - 1);
+            assert_eq!(governance.queue_proposal(proposal_id), Err(Error::VotingPeriodNotEnded));
+
+            test::set_block_timestamp::<DefaultEnvironment>(voting_ends_at);
+            set_caller(accounts.django);
+            assert_eq!(governance.vote(proposal_id, VoteType::Against), Err(Error::VotingPeriodEnded));
+            assert_eq!(governance.queue_proposal(proposal_id), Ok(()));
+
+            let proposal = governance.get_proposal(proposal_id).unwrap();
+            assert_eq!(proposal.status, ProposalStatus::Passed);
+            assert_eq!(proposal.executable_at, voting_ends_at + TIMELOCK_DELAY);
+            assert_eq!(proposal.expires_at, proposal.executable_at + EXECUTION_WINDOW);
+        }
+
+        #[ink::test]
+        fn queued_proposals_wait_for_the_timelock() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let proposal_id = propose(&mut governance, accounts.alice);
+            assert_eq!(governance.execute_proposal(proposal_id), Err(Error::ProposalNotQueued));
+            pass(&mut governance, proposal_id);
+
+            let executable_at = governance.get_proposal(proposal_id).unwrap().executable_at;
+            test::set_block_timestamp::<DefaultEnvironment>(executable_at - 1);
+            assert_eq!(governance.execute_proposal(proposal_id), Err(Error::TimelockNotElapsed));
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().status, ProposalStatus::Passed);
+
+            test::set_block_timestamp::<DefaultEnvironment>(executable_at);
+            assert_eq!(governance.execute_proposal(proposal_id), Ok(()));
+        }
+
+        #[ink::test]
+        fn proposals_left_past_their_window_expire() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let proposal_id = propose(&mut governance, accounts.alice);
+            pass(&mut governance, proposal_id);
+
+            let expires_at = governance.get_proposal(proposal_id).unwrap().expires_at;
+            test::set_block_timestamp::<DefaultEnvironment>(expires_at);
+            assert_eq!(governance.execute_proposal(proposal_id), Ok(()));
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().status, ProposalStatus::Expired);
+            assert!(executed_with(proposal_id, ProposalStatus::Expired));
+            assert_eq!(governance.execute_proposal(proposal_id), Err(Error::ProposalNotQueued));
+        }
+
+        #[ink::test]
+        fn only_governance_changes_the_timing() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let timing = ProposalTiming {
+                voting_period: 2_000,
+                timelock_delay: 0,
+                execution_window: 500,
+            };
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.set_timing(timing.clone()), Err(Error::Unauthorized));
+
+            set_caller(test::callee::<DefaultEnvironment>());
+            let invalid = ProposalTiming {
+                voting_period: 0,
+                ..timing.clone()
+            };
+            assert_eq!(governance.set_timing(invalid), Err(Error::InvalidTiming));
+            assert_eq!(governance.set_timing(timing.clone()), Ok(()));
+            assert_eq!(governance.get_timing(), timing);
+
+            let proposal_id = propose(&mut governance, accounts.alice);
+            let created_at = ink::env::block_timestamp::<DefaultEnvironment>();
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().voting_ends_at, created_at + 2_000);
+        }
+
+        #[ink::test]
+        fn only_governance_switches_the_voting_token() {
+            let accounts = accounts();
+            let mut governance = governance();
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.set_voting_token(Some(accounts.frank)), Err(Error::Unauthorized));
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.set_voting_token(Some(accounts.frank)), Ok(()));
+            assert_eq!(governance.get_voting_token(), Some(accounts.frank));
+            assert_eq!(governance.set_voting_token(None), Ok(()));
+
+            let proposal_id = propose(&mut governance, accounts.alice);
+            let proposal = governance.get_proposal(proposal_id).unwrap();
+            assert_eq!((proposal.voting_token, proposal.voting_supply), (None, TOTAL_SUPPLY));
+        }
+
+        #[ink::test]
+        fn votes_use_the_power_held_before_the_proposal() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 3);
+            let proposal_id = propose(&mut governance, accounts.alice);
+            let snapshot_block = governance.get_proposal(proposal_id).unwrap().snapshot_block;
+
+            // Raised in the proposal's block and again in a later one
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            test::advance_block::<DefaultEnvironment>();
+            grant_voting_power(&mut governance, accounts.charlie, 20);
+            assert_eq!(governance.get_citizen_profile(accounts.charlie).voting_power, 20);
+            assert_eq!(governance.voting_power_at(accounts.charlie, snapshot_block), 3);
+            assert_eq!(governance.voting_power_at(accounts.charlie, snapshot_block + 2), 20);
+            assert_eq!(governance.voting_power_at(accounts.django, snapshot_block), 1);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().votes_for, 3);
+        }
+
+        #[ink::test]
+        fn checkpoints_written_in_one_block_are_merged() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 3);
+            grant_voting_power(&mut governance, accounts.charlie, 5);
+            assert_eq!(governance.get_checkpoint_count(accounts.charlie), 1);
+            assert_eq!(governance.get_checkpoint(accounts.charlie, 0).unwrap().voting_power, 5);
+
+            test::advance_block::<DefaultEnvironment>();
+            grant_voting_power(&mut governance, accounts.charlie, 7);
+            assert_eq!(governance.get_checkpoint_count(accounts.charlie), 2);
+        }
+
+        #[ink::test]
+        fn delegates_vote_with_their_delegators_power() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 3);
+            grant_voting_power(&mut governance, accounts.django, 5);
+
+            set_caller(accounts.django);
+            assert_eq!(governance.delegate(accounts.django), Err(Error::SelfDelegation));
+            assert_eq!(governance.delegate(accounts.charlie), Ok(()));
+            assert_eq!(governance.get_delegate(accounts.django), Some(accounts.charlie));
+            set_caller(accounts.charlie);
+            assert_eq!(governance.delegate(accounts.django), Err(Error::DelegationCycle));
+            assert_eq!(governance.undelegate(), Err(Error::NotDelegating));
+
+            let proposal_id = propose(&mut governance, accounts.alice);
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().votes_for, 8);
+        }
+
+        #[ink::test]
+        fn delegation_chains_are_capped() {
+            let accounts = accounts();
+            let mut governance = governance();
+            set_caller(accounts.django);
+            assert_eq!(governance.delegate(accounts.charlie), Ok(()));
+            set_caller(accounts.eve);
+            assert_eq!(governance.delegate(accounts.django), Ok(()));
+            set_caller(accounts.frank);
+            assert_eq!(governance.delegate(accounts.eve), Err(Error::DelegationTooDeep));
+
+            set_caller(accounts.eve);
+            assert_eq!(governance.undelegate(), Ok(()));
+            assert_eq!(governance.get_delegators(accounts.django), Vec::new());
+        }
+
+        #[ink::test]
+        fn quadratic_votes_count_the_square_root_of_credits() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let proposal_id = propose_quadratic(&mut governance, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Err(Error::WrongVotingScheme));
+            assert_eq!(
+                governance.vote_with_credits(proposal_id, VoteType::For, DEFAULT_VOICE_CREDITS + 1),
+                Err(Error::InsufficientVoiceCredits)
+            );
+            assert_eq!(governance.vote_with_credits(proposal_id, VoteType::For, 50), Ok(()));
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().votes_for, 7);
+            assert_eq!(governance.voice_credits_remaining(accounts.charlie), DEFAULT_VOICE_CREDITS - 50);
+        }
+
+        #[ink::test]
+        fn abstentions_count_towards_quorum_only() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let abstained = propose(&mut governance, accounts.alice);
+            let unattended = propose(&mut governance, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(abstained, VoteType::Abstain), Ok(()));
+            set_caller(accounts.django);
+            assert_eq!(governance.vote(abstained, VoteType::For), Ok(()));
+            assert_eq!(governance.vote(unattended, VoteType::For), Ok(()));
+
+            let proposal = governance.get_proposal(abstained).unwrap();
+            assert_eq!((proposal.votes_for, proposal.votes_against, proposal.votes_abstain), (1, 0, 10));
+
+            end_voting(&governance, unattended);
+            assert_eq!(governance.queue_proposal(abstained), Ok(()));
+            assert_eq!(governance.get_proposal(abstained).unwrap().status, ProposalStatus::Passed);
+            assert_eq!(governance.queue_proposal(unattended), Ok(()));
+            assert_eq!(governance.get_proposal(unattended).unwrap().status, ProposalStatus::Rejected);
+        }
+
+        #[ink::test]
+        fn proposals_with_only_abstentions_are_rejected() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let proposal_id = propose(&mut governance, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::Abstain), Ok(()));
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Err(Error::AlreadyVoted));
+            end_voting(&governance, proposal_id);
+            assert_eq!(governance.queue_proposal(proposal_id), Ok(()));
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().status, ProposalStatus::Rejected);
+        }
+
+        #[ink::test]
+        fn change_vote_moves_the_full_weight() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 3);
+            let proposal_id = propose(&mut governance, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.change_vote(proposal_id, VoteType::Against), Err(Error::NotVoted));
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            assert_eq!(governance.vote(proposal_id, VoteType::Against), Err(Error::AlreadyVoted));
+            assert_eq!(governance.change_vote(proposal_id, VoteType::Against), Ok(()));
+
+            let proposal = governance.get_proposal(proposal_id).unwrap();
+            assert_eq!((proposal.votes_for, proposal.votes_against), (0, 3));
+        }
+
+        #[ink::test]
+        fn voting_again_after_withdrawal_is_not_rewarded_twice() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let proposal_id = propose(&mut governance, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            let rewarded = governance.get_citizen_profile(accounts.charlie);
+            assert_eq!(governance.withdraw_vote(proposal_id), Ok(()));
+            assert_eq!(governance.get_vote(proposal_id, accounts.charlie), None);
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().votes_for, 0);
+
+            assert_eq!(governance.vote(proposal_id, VoteType::Abstain), Ok(()));
+            assert_eq!(governance.get_citizen_profile(accounts.charlie), rewarded);
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().votes_abstain, 1);
+        }
+
+        #[ink::test]
+        fn withdrawn_delegate_votes_return_power_to_delegators() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 3);
+            grant_voting_power(&mut governance, accounts.django, 5);
+            set_caller(accounts.django);
+            assert_eq!(governance.delegate(accounts.charlie), Ok(()));
+            let proposal_id = propose(&mut governance, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            assert_eq!(governance.withdraw_vote(proposal_id), Ok(()));
+
+            set_caller(accounts.django);
+            assert_eq!(governance.vote(proposal_id, VoteType::Against), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+
+            let proposal = governance.get_proposal(proposal_id).unwrap();
+            assert_eq!((proposal.votes_for, proposal.votes_against), (3, 5));
+        }
+
+        #[ink::test]
+        fn delegators_voting_directly_override_their_delegate() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 3);
+            grant_voting_power(&mut governance, accounts.django, 5);
+            set_caller(accounts.django);
+            assert_eq!(governance.delegate(accounts.charlie), Ok(()));
+            let proposal_id = propose(&mut governance, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            set_caller(accounts.django);
+            assert_eq!(governance.vote(proposal_id, VoteType::Against), Ok(()));
+
+            let proposal = governance.get_proposal(proposal_id).unwrap();
+            assert_eq!((proposal.votes_for, proposal.votes_against), (3, 5));
+            assert_eq!(governance.get_vote(proposal_id, accounts.charlie).unwrap().voting_power, 3);
+        }
+
+        #[ink::test]
+        fn only_the_oracle_submits_analyses() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let proposal_id = propose(&mut governance, accounts.alice);
+            let summary_hash = Hash::from([7; 32]);
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                governance.submit_ai_analysis(proposal_id, 87, RiskLevel::Medium, summary_hash),
+                Err(Error::Unauthorized)
+            );
+
+            // Bob, the mock oracle, answers the request
+            set_caller(accounts.bob);
+            assert_eq!(
+                governance.submit_ai_analysis(proposal_id, 101, RiskLevel::Medium, summary_hash),
+                Err(Error::InvalidScore)
+            );
+            assert_eq!(governance.submit_ai_analysis(proposal_id, 87, RiskLevel::Medium, summary_hash), Ok(()));
+            assert_eq!(
+                governance.submit_ai_analysis(proposal_id, 40, RiskLevel::High, summary_hash),
+                Err(Error::AnalysisAlreadySubmitted)
+            );
+
+            let recommendation = governance.get_proposal(proposal_id).unwrap().ai_recommendation.unwrap();
+            assert_eq!(recommendation.score, 87);
+            assert_eq!(recommendation.risk_level, RiskLevel::Medium);
+            assert_eq!(recommendation.summary_hash, summary_hash);
+        }
+
+        #[ink::test]
+        fn creating_a_proposal_requests_an_analysis() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let proposal_id = propose(&mut governance, accounts.alice);
+
+            let events = test::recorded_events().collect::<Vec<_>>();
+            let requested = events
+                .iter()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .any(|event| {
+                    matches!(event, Event::AnalysisRequested(AnalysisRequested { proposal_id: id, oracle })
+                        if id == proposal_id && oracle == accounts.bob)
+                });
+            assert!(requested);
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().ai_recommendation, None);
+        }
+
+        #[ink::test]
+        fn analyses_are_only_accepted_while_voting_is_open() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let proposal_id = propose(&mut governance, accounts.alice);
+
+            end_voting(&governance, proposal_id);
+            set_caller(accounts.bob);
+            assert_eq!(
+                governance.submit_ai_analysis(proposal_id, 87, RiskLevel::Low, Hash::from([0; 32])),
+                Err(Error::VotingPeriodEnded)
+            );
+        }
+
+        #[ink::test]
+        fn proposals_are_cancelled_by_their_proposer_before_any_vote() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let first = propose(&mut governance, accounts.alice);
+            let second = propose(&mut governance, accounts.alice);
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.cancel_proposal(first), Err(Error::NotProposer));
+            set_caller(accounts.alice);
+            assert_eq!(governance.cancel_proposal(first), Ok(()));
+            assert_eq!(governance.get_proposal(first).unwrap().status, ProposalStatus::Cancelled);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(second, VoteType::Abstain), Ok(()));
+            set_caller(accounts.alice);
+            assert_eq!(governance.cancel_proposal(second), Err(Error::VotesAlreadyCast));
+        }
+
+        #[ink::test]
+        fn the_guardian_vetoes_only_during_the_timelock() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let first = propose(&mut governance, accounts.alice);
+            let second = propose(&mut governance, accounts.alice);
+            pass(&mut governance, first);
+            pass(&mut governance, second);
+
+            set_caller(accounts.alice);
+            assert_eq!(governance.veto_proposal(first), Err(Error::Unauthorized));
+            set_caller(accounts.eve);
+            assert_eq!(governance.veto_proposal(first), Ok(()));
+            assert_eq!(governance.get_proposal(first).unwrap().status, ProposalStatus::Vetoed);
+            assert_eq!(governance.veto_proposal(first), Err(Error::ProposalNotQueued));
+
+            let executable_at = governance.get_proposal(second).unwrap().executable_at;
+            test::set_block_timestamp::<DefaultEnvironment>(executable_at);
+            assert_eq!(governance.veto_proposal(second), Err(Error::VetoWindowClosed));
+        }
+
+        #[ink::test]
+        fn amending_supersedes_the_proposal_and_restarts_voting() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let proposal_id = propose(&mut governance, accounts.alice);
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+
+            set_caller(accounts.alice);
+            let revision_id = governance
+                .amend_proposal(proposal_id, String::from("Revised"), String::new(), 0, None, Vec::new())
+                .unwrap();
+
+            let original = governance.get_proposal(proposal_id).unwrap();
+            assert_eq!(original.status, ProposalStatus::Superseded);
+            assert_eq!(original.superseded_by, Some(revision_id));
+            let revision = governance.get_proposal(revision_id).unwrap();
+            assert_eq!(revision.revision_of, Some(proposal_id));
+            assert_eq!((revision.status, revision.votes_for), (ProposalStatus::Active, 0));
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Err(Error::ProposalNotActive));
+            assert_eq!(governance.vote(revision_id, VoteType::For), Ok(()));
+        }
+
+        #[ink::test]
+        fn exponential_decay_halves_per_half_life() {
+            let decay = ReputationDecay::Exponential { half_life: 1_000 };
+            assert_eq!(decay.apply(800, 0), 800);
+            assert_eq!(decay.apply(800, 500), 600);
+            assert_eq!(decay.apply(800, 1_000), 400);
+            assert_eq!(decay.apply(800, 2_500), 150);
+            assert_eq!(decay.apply(800, 32_000), 0);
+        }
+
+        #[ink::test]
+        fn exponential_decay_handles_long_half_lives() {
+            let decay = ReputationDecay::Exponential { half_life: u64::MAX };
+            assert_eq!(decay.apply(u32::MAX, u64::MAX - 1), u32::MAX / 2 + 1);
+        }
+
+        #[ink::test]
+        fn step_decay_compounds_per_full_period() {
+            let decay = ReputationDecay::Step { period: 100, rate_bps: 1_000 };
+            assert_eq!(decay.apply(1_000, 99), 1_000);
+            assert_eq!(decay.apply(1_000, 100), 900);
+            assert_eq!(decay.apply(1_000, 350), 729);
+            assert!(!ReputationDecay::Step { period: 100, rate_bps: 10_001 }.is_valid());
+            assert!(!ReputationDecay::Exponential { half_life: 0 }.is_valid());
+        }
+
+        #[ink::test]
+        fn inactive_citizens_lose_voting_power() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 8);
+
+            set_caller(test::callee::<DefaultEnvironment>());
+            let decay = ReputationDecay::Exponential { half_life: 1_000 };
+            assert_eq!(governance.set_reputation_decay(decay), Ok(()));
+
+            test::set_block_timestamp::<DefaultEnvironment>(2_000);
+            assert_eq!(governance.get_effective_reputation(accounts.charlie), 200);
+            let proposal_id = propose(&mut governance, accounts.alice);
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            assert_eq!(governance.get_vote(proposal_id, accounts.charlie).unwrap().voting_power, 2);
+        }
+
+        /// Requires `deposit` from proposers and funds the contract with it.
+        fn require_deposit(governance: &mut CityGovernance, deposit: Balance) {
+            let contract = test::callee::<DefaultEnvironment>();
+            set_caller(contract);
+            let requirements = ProposalRequirements { threshold: 0, deposit };
+            assert_eq!(governance.set_proposal_requirements(requirements), Ok(()));
+            test::set_account_balance::<DefaultEnvironment>(contract, deposit);
+            test::set_value_transferred::<DefaultEnvironment>(deposit);
+        }
+
+        #[ink::test]
+        fn proposers_need_the_threshold_and_the_exact_deposit() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.django, 5);
+            let requirements = ProposalRequirements { threshold: 500, deposit: 40 };
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.set_proposal_requirements(requirements.clone()), Err(Error::Unauthorized));
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.set_proposal_requirements(requirements.clone()), Ok(()));
+            assert_eq!(governance.get_proposal_requirements(), requirements);
+
+            let create = |governance: &mut CityGovernance| {
+                let title = String::from("Proposal");
+                governance.create_proposal(title, String::new(), 0, None, Vec::new(), VotingScheme::Linear, None)
+            };
+            set_caller(accounts.alice);
+            test::set_value_transferred::<DefaultEnvironment>(40);
+            assert_eq!(create(&mut governance), Err(Error::BelowProposalThreshold));
+
+            set_caller(accounts.django);
+            test::set_value_transferred::<DefaultEnvironment>(39);
+            assert_eq!(create(&mut governance), Err(Error::WrongDeposit));
+            test::set_value_transferred::<DefaultEnvironment>(41);
+            assert_eq!(create(&mut governance), Err(Error::WrongDeposit));
+            test::set_value_transferred::<DefaultEnvironment>(40);
+            let proposal_id = create(&mut governance).unwrap();
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().deposit, 40);
+        }
+
+        #[ink::test]
+        fn passed_proposals_leave_deposits_to_claim() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 50);
+            require_deposit(&mut governance, 40);
+            let proposal_id = propose(&mut governance, accounts.django);
+            test::set_value_transferred::<DefaultEnvironment>(0);
+
+            pass(&mut governance, proposal_id);
+            assert_eq!(governance.get_claimable_deposit(accounts.django), 40);
+
+            let before = test::get_account_balance::<DefaultEnvironment>(accounts.django).unwrap();
+            set_caller(accounts.django);
+            assert_eq!(governance.claim_deposit(), Ok(40));
+            assert_eq!(governance.claim_deposit(), Ok(0));
+            assert_eq!(governance.get_claimable_deposit(accounts.django), 0);
+            let after = test::get_account_balance::<DefaultEnvironment>(accounts.django).unwrap();
+            assert_eq!(after - before, 40);
+        }
+
+        #[ink::test]
+        fn proposals_missing_quorum_forfeit_their_deposit() {
+            let accounts = accounts();
+            let mut governance = governance();
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.set_treasury(Some(accounts.frank)), Ok(()));
+            require_deposit(&mut governance, 40);
+            let proposal_id = propose(&mut governance, accounts.django);
+            test::set_value_transferred::<DefaultEnvironment>(0);
+
+            let before = test::get_account_balance::<DefaultEnvironment>(accounts.frank).unwrap();
+            end_voting(&governance, proposal_id);
+            assert_eq!(governance.queue_proposal(proposal_id), Ok(()));
+            let proposal = governance.get_proposal(proposal_id).unwrap();
+            assert_eq!((proposal.status, proposal.deposit), (ProposalStatus::Rejected, 0));
+            let after = test::get_account_balance::<DefaultEnvironment>(accounts.frank).unwrap();
+            assert_eq!(after - before, 40);
+            assert_eq!(governance.get_claimable_deposit(accounts.django), 0);
+
+            let slashed = test::recorded_events()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .any(|event| {
+                    matches!(event, Event::DepositSettled(DepositSettled { proposal_id: id, amount, refunded })
+                        if id == proposal_id && amount == 40 && !refunded)
+                });
+            assert!(slashed);
+        }
+
+        #[ink::test]
+        fn the_deployer_wires_only_the_first_citizen_registry() {
+            let accounts = accounts();
+            let mut governance = governance();
+            assert_eq!(governance.get_citizen_registry(), None);
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.set_citizen_registry(Some(accounts.frank)), Err(Error::Unauthorized));
+            set_caller(accounts.alice);
+            assert_eq!(governance.set_citizen_registry(Some(accounts.frank)), Ok(()));
+            assert_eq!(governance.get_citizen_registry(), Some(accounts.frank));
+
+            // Alice is also this contract's account off-chain, so use Charlie
+            test::set_callee::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(governance.set_citizen_registry(None), Err(Error::Unauthorized));
+            set_caller(accounts.charlie);
+            assert_eq!(governance.set_citizen_registry(None), Ok(()));
+            assert_eq!(governance.get_citizen_registry(), None);
+        }
+
+        #[ink::test]
+        fn closed_voting_waits_for_a_citizen_registry() {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut governance = CityGovernance::new(
+                TOTAL_SUPPLY,
+                None,
+                accounts.bob,
+                Some(accounts.eve),
+                false,
+                VOTING_PERIOD,
+                TIMELOCK_DELAY,
+                EXECUTION_WINDOW,
+            );
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let proposal_id = propose(&mut governance, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Err(Error::NotCitizen));
+            assert!(governance.get_vote(proposal_id, accounts.charlie).is_none());
+        }
+
+        fn ids(proposals: Vec<Proposal>) -> Vec<u32> {
+            proposals.iter().map(|proposal| proposal.id).collect()
+        }
+
+        #[ink::test]
+        fn proposals_are_listed_in_bounded_pages() {
+            let accounts = accounts();
+            let mut governance = governance();
+            for _ in 0..=MAX_PAGE_SIZE {
+                propose(&mut governance, accounts.alice);
+            }
+            assert_eq!(governance.proposal_count(), MAX_PAGE_SIZE + 1);
+
+            assert_eq!(ids(governance.list_proposals(0, 3, None)), vec![0, 1, 2]);
+            assert_eq!(ids(governance.list_proposals(MAX_PAGE_SIZE - 1, 10, None)), vec![49, 50]);
+            assert_eq!(governance.list_proposals(0, u32::MAX, None).len(), MAX_PAGE_SIZE as usize);
+            assert_eq!(governance.list_proposals(u32::MAX, u32::MAX, None), Vec::new());
+        }
+
+        #[ink::test]
+        fn listings_filter_by_status_kind() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let first = propose(&mut governance, accounts.alice);
+            let second = propose(&mut governance, accounts.alice);
+            set_caller(accounts.alice);
+            assert_eq!(governance.cancel_proposal(first), Ok(()));
+
+            let cancelled = governance.list_proposals(0, 10, Some(ProposalStatus::Cancelled));
+            assert_eq!(ids(cancelled), vec![first]);
+            let active = governance.list_proposals(0, 10, Some(ProposalStatus::Active));
+            assert_eq!(ids(active), vec![second]);
+
+            // Only the variant matters, not the failure details
+            let failed = ProposalStatus::ExecutionFailed {
+                call_index: 0,
+                reason: CallFailure::Other,
+            };
+            assert_eq!(governance.list_proposals(0, 10, Some(failed)), Vec::new());
+        }
+
+        #[ink::test]
+        fn proposals_are_listed_per_proposer() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let first = propose(&mut governance, accounts.alice);
+            let other = propose(&mut governance, accounts.charlie);
+            let second = propose(&mut governance, accounts.alice);
+
+            assert_eq!(governance.proposals_by_proposer_count(accounts.alice), 2);
+            assert_eq!(governance.proposals_by_proposer(accounts.alice, 0, 10), vec![first, second]);
+            assert_eq!(governance.proposals_by_proposer(accounts.alice, 1, 10), vec![second]);
+            assert_eq!(governance.proposals_by_proposer(accounts.alice, 0, 1), vec![first]);
+            assert_eq!(governance.proposals_by_proposer(accounts.charlie, 0, 10), vec![other]);
+            assert_eq!(governance.proposals_by_proposer(accounts.django, 0, 10), Vec::new());
+        }
+
+        #[ink::test]
+        fn votes_are_read_back_with_their_power() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 4);
+            let proposal_id = propose(&mut governance, accounts.alice);
+            assert_eq!(governance.get_vote(proposal_id, accounts.charlie), None);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::Against), Ok(()));
+            let record = VoteRecord {
+                vote: VoteType::Against,
+                voting_power: 4,
+            };
+            assert_eq!(governance.get_vote(proposal_id, accounts.charlie), Some(record));
+        }
+
+        #[ink::test]
+        fn only_governance_upgrades_and_migrates() {
+            let accounts = accounts();
+            let mut governance = governance();
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.set_code(Hash::from([1; 32])), Err(Error::Unauthorized));
+            assert_eq!(governance.migrate(0, 10), Err(Error::Unauthorized));
+
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.migrate(0, 10), Ok(()));
+            assert_eq!(governance.get_storage_version(), STORAGE_VERSION);
+        }
+
+        #[ink::test]
+        fn migrate_refuses_newer_storage() {
+            let mut governance = governance();
+            governance.storage_version.set(&(STORAGE_VERSION + 1));
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.migrate(0, 10), Err(Error::UnsupportedStorageVersion));
+        }
+
+        #[ink::test]
+        fn pausers_halt_functions_until_governance_resumes() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 10);
+            let proposal_id = propose(&mut governance, accounts.alice);
+            let vote = ink::selector_bytes!("vote");
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.pause(vote), Err(PauseError::Unauthorized));
+            set_caller(accounts.alice);
+            assert_eq!(governance.pause(vote), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Err(Error::Paused));
+            assert_eq!(governance.delegate(accounts.django), Ok(()));
+            assert_eq!(governance.undelegate(), Ok(()));
+
+            // Lifting the single pause leaves a global one in force
+            set_caller(accounts.alice);
+            assert_eq!(governance.pause(ALL_FUNCTIONS), Ok(()));
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.unpause(vote), Ok(()));
+            assert!(governance.is_paused(vote));
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.unpause(ALL_FUNCTIONS), Err(PauseError::Unauthorized));
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.unpause(ALL_FUNCTIONS), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+        }
+
+        #[ink::test]
+        fn zone_proposals_need_a_zone_registry() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let create = |governance: &mut CityGovernance, zone: Option<ZoneId>| {
+                let title = String::from("Proposal");
+                governance.create_proposal(title, String::new(), 0, None, Vec::new(), VotingScheme::Linear, zone)
+            };
+
+            set_caller(accounts.alice);
+            assert_eq!(create(&mut governance, Some(0)), Err(Error::ZoneRegistryNotSet));
+            let proposal_id = create(&mut governance, None).expect("city-wide proposal is created");
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().zone, None);
+        }
+
+        #[ink::test]
+        fn the_deployer_wires_only_the_first_zone_registry() {
+            let accounts = accounts();
+            let mut governance = governance();
+            // Moves the contract off Alice so that she is only the deployer
+            test::set_callee::<DefaultEnvironment>(accounts.charlie);
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.set_zone_registry(Some(accounts.django)), Err(Error::Unauthorized));
+            set_caller(accounts.alice);
+            assert_eq!(governance.set_zone_registry(Some(accounts.django)), Ok(()));
+            assert_eq!(governance.get_zone_registry(), Some(accounts.django));
+            assert_eq!(governance.set_zone_registry(Some(accounts.eve)), Err(Error::Unauthorized));
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.set_zone_registry(None), Ok(()));
+            assert_eq!(governance.get_zone_registry(), None);
+        }
+
+        #[ink::test]
+        fn only_the_proposal_being_executed_is_run() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let proposal_id = propose(&mut governance, accounts.alice);
+            let governance_account = test::callee::<DefaultEnvironment>();
+
+            set_caller(governance_account);
+            assert_eq!(governance.run_proposal(proposal_id), Err(Error::Unauthorized));
+
+            governance.executing.set(&Some(proposal_id));
+            set_caller(accounts.bob);
+            assert_eq!(governance.run_proposal(proposal_id), Err(Error::Unauthorized));
+            set_caller(governance_account);
+            assert_eq!(governance.run_proposal(proposal_id + 1), Err(Error::Unauthorized));
+            assert_eq!(governance.run_proposal(proposal_id), Ok(()));
+            // A call of the proposal re-entering `run_proposal` is turned away
+            assert_eq!(governance.run_proposal(proposal_id), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn redelegating_to_the_same_delegate_counts_once() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 3);
+            grant_voting_power(&mut governance, accounts.django, 5);
+
+            set_caller(accounts.django);
+            assert_eq!(governance.delegate(accounts.charlie), Ok(()));
+            assert_eq!(governance.delegate(accounts.charlie), Ok(()));
+            assert_eq!(governance.get_delegators(accounts.charlie), vec![accounts.django]);
+
+            let proposal_id = propose(&mut governance, accounts.alice);
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            assert_eq!(governance.get_proposal(proposal_id).unwrap().votes_for, 8);
+        }
+
+        #[ink::test]
+        fn delegators_listed_twice_are_counted_once() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 3);
+            grant_voting_power(&mut governance, accounts.django, 5);
+            governance.delegates.insert(accounts.django, &accounts.charlie);
+            governance.delegators.insert(accounts.charlie, &vec![accounts.django, accounts.django]);
+
+            let proposal_id = propose(&mut governance, accounts.alice);
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            assert_eq!(governance.get_vote(proposal_id, accounts.charlie).unwrap().voting_power, 8);
+        }
+
+        #[ink::test]
+        fn lowering_the_credit_budget_below_spending_leaves_nothing() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let first = propose_quadratic(&mut governance, accounts.alice);
+            let second = propose_quadratic(&mut governance, accounts.alice);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote_with_credits(first, VoteType::For, 64), Ok(()));
+
+            set_caller(test::callee::<DefaultEnvironment>());
+            let config = VoiceCreditConfig {
+                epoch_length: DEFAULT_EPOCH_LENGTH,
+                credits_per_epoch: 16,
+            };
+            assert_eq!(governance.set_voice_credit_config(config), Ok(()));
+
+            assert_eq!(governance.voice_credits_remaining(accounts.charlie), 0);
+            set_caller(accounts.charlie);
+            assert_eq!(
+                governance.vote_with_credits(second, VoteType::For, 1),
+                Err(Error::InsufficientVoiceCredits)
+            );
+        }
+
+        #[ink::test]
+        fn pauses_lapse_and_cool_down() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let vote = ink::selector_bytes!("vote");
+            let delegate = ink::selector_bytes!("delegate");
+
+            assert_eq!(governance.pause(vote), Ok(()));
+            test::set_block_timestamp::<DefaultEnvironment>(PAUSE_DURATION - 1);
+            assert_eq!(governance.pause(delegate), Ok(()));
+            assert!(governance.is_paused(vote) && governance.is_paused(delegate));
+
+            // Both pauses close with the window they were opened in
+            test::set_block_timestamp::<DefaultEnvironment>(PAUSE_DURATION);
+            assert!(!governance.is_paused(vote) && !governance.is_paused(delegate));
+            assert_eq!(governance.pause(ALL_FUNCTIONS), Err(PauseError::CoolingDown));
+
+            test::set_block_timestamp::<DefaultEnvironment>(PAUSE_DURATION + PAUSE_COOLDOWN);
+            assert_eq!(governance.pause(ALL_FUNCTIONS), Ok(()));
+            assert!(governance.is_paused(vote));
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.unpause(ALL_FUNCTIONS), Err(PauseError::Unauthorized));
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.unpause(ALL_FUNCTIONS), Ok(()));
+            assert!(!governance.is_paused(vote));
+        }
+
+        /// Writes proposal `id` as storage version 1 laid it out, and rolls
+        /// the contract back to that version.
+        fn store_version_one_proposal(governance: &mut CityGovernance, id: u32) {
+            let stored = ProposalV1 {
+                id,
+                title: String::from("Proposal"),
+                description: String::new(),
+                proposer: accounts().charlie,
+                votes_for: 7,
+                votes_against: 2,
+                votes_abstain: 0,
+                status: ProposalStatus::Passed,
+                voting_scheme: VotingScheme::Linear,
+                voting_ends_at: 10,
+                executable_at: 20,
+                expires_at: 30,
+                budget: 0,
+                deposit: 0,
+                funding: None,
+                ai_recommendation: None,
+                calls: Vec::new(),
+                voting_token: None,
+                voting_supply: TOTAL_SUPPLY,
+                revision_of: None,
+                superseded_by: None,
+                snapshot_block: 1,
+            };
+            ink::env::set_contract_storage(&(governance.proposals.key(), id), &stored);
+            governance.proposal_count = governance.proposal_count.max(id + 1);
+            governance.storage_version.set(&1);
+        }
+
+        #[ink::test]
+        fn migrate_upgrades_version_one_proposals() {
+            let accounts = accounts();
+            let mut governance = governance();
+            store_version_one_proposal(&mut governance, 0);
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.migrate(0, 10), Err(Error::Unauthorized));
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.migrate(0, 10), Ok(()));
+            assert_eq!(governance.get_storage_version(), STORAGE_VERSION);
+
+            let proposal = governance.get_proposal(0).unwrap();
+            assert_eq!((proposal.proposer, proposal.votes_for, proposal.expires_at), (accounts.charlie, 7, 30));
+            assert_eq!((proposal.zone, proposal.voter_count), (None, 0));
+            assert_eq!(proposal.snapshot_block, 1);
+        }
+
+        #[ink::test]
+        fn migrations_run_in_ordered_batches() {
+            let accounts = accounts();
+            let mut governance = governance();
+            for id in 0..3 {
+                store_version_one_proposal(&mut governance, id);
+            }
+
+            // Anyone can carry on a migration, but only governance starts one
+            set_caller(accounts.bob);
+            assert_eq!(governance.migrate(0, 2), Err(Error::Unauthorized));
+            set_caller(test::callee::<DefaultEnvironment>());
+            assert_eq!(governance.migrate(0, 2), Ok(()));
+            assert_eq!(governance.get_migration_cursor(), Some(2));
+            assert_eq!(governance.get_storage_version(), 1);
+            assert_eq!(governance.get_proposal(1).unwrap().zone, None);
+
+            set_caller(accounts.bob);
+            assert_eq!(governance.migrate(0, 2), Err(Error::MigrationOutOfOrder));
+            assert_eq!(governance.migrate(3, 2), Err(Error::MigrationOutOfOrder));
+            assert_eq!(governance.migrate(2, 2), Ok(()));
+            assert_eq!(governance.get_migration_cursor(), None);
+            assert_eq!(governance.get_storage_version(), STORAGE_VERSION);
+            assert_eq!(governance.get_proposal(2).unwrap().votes_for, 7);
+
+            let migrated = test::recorded_events()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .filter(|event| {
+                    matches!(event, Event::StorageMigrated(StorageMigrated { from: 1, to: STORAGE_VERSION }))
+                })
+                .count();
+            assert_eq!(migrated, 1);
+            // With the migration over, starting another takes governance again
+            assert_eq!(governance.migrate(0, 2), Err(Error::Unauthorized));
+        }
+
+        #[ink::test]
+        fn delegators_count_towards_the_headcount() {
+            let accounts = accounts();
+            let mut governance = governance();
+            grant_voting_power(&mut governance, accounts.charlie, 3);
+            grant_voting_power(&mut governance, accounts.django, 5);
+            grant_voting_power(&mut governance, accounts.frank, 2);
+            set_caller(accounts.django);
+            assert_eq!(governance.delegate(accounts.charlie), Ok(()));
+            set_caller(accounts.frank);
+            assert_eq!(governance.delegate(accounts.charlie), Ok(()));
+            let proposal_id = propose(&mut governance, accounts.alice);
+            let voter_count = |governance: &CityGovernance| governance.get_proposal(proposal_id).unwrap().voter_count;
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            assert_eq!(voter_count(&governance), 3);
+
+            // A delegator voting directly moves their head, not a second one
+            set_caller(accounts.django);
+            assert_eq!(governance.vote(proposal_id, VoteType::Against), Ok(()));
+            assert_eq!(voter_count(&governance), 3);
+
+            set_caller(accounts.charlie);
+            assert_eq!(governance.withdraw_vote(proposal_id), Ok(()));
+            assert_eq!(voter_count(&governance), 1);
+        }
+
+        #[ink::test]
+        fn zone_quorum_counts_heads_against_the_population() {
+            let accounts = accounts();
+            let mut governance = governance();
+            let proposal_id = propose(&mut governance, accounts.alice);
+            let mut proposal = governance.get_proposal(proposal_id).unwrap();
+            proposal.zone = Some(0);
+            proposal.voting_supply = 30;
+            // Power far above the population does not make up for heads
+            proposal.votes_for = 1_000;
+            proposal.voter_count = 2;
+            assert!(!proposal.quorum_reached());
+            proposal.voter_count = 3;
+            assert!(proposal.quorum_reached());
+
+            proposal.zone = None;
+            proposal.votes_for = 2;
+            assert!(!proposal.quorum_reached());
+            proposal.votes_abstain = 1;
+            assert!(proposal.quorum_reached());
+        }
+
+        #[ink::test]
+        fn reverted_runs_report_the_failing_call() {
+            let failed = Error::CallFailed {
+                call_index: 1,
+                reason: CallFailure::Reverted(vec![3]),
+            };
+            assert_eq!(
+                CityGovernance::failed_call(CallFailure::Reverted(scale::Encode::encode(&failed))),
+                (1, CallFailure::Reverted(vec![3]))
+            );
+            assert_eq!(
+                CityGovernance::failed_call(CallFailure::Reverted(vec![0xff])),
+                (0, CallFailure::Reverted(vec![0xff]))
+            );
+            assert_eq!(CityGovernance::failed_call(CallFailure::CalleeTrapped), (0, CallFailure::CalleeTrapped));
+        }
+
+        #[ink::test]
+        fn calls_fail_when_storage_cannot_be_reloaded() {
+            let mut governance = governance();
+            let key = <CityGovernance as ink::storage::traits::StorageKey>::KEY;
+            ink::env::set_contract_storage(&key, &governance);
+            propose(&mut governance, accounts().alice);
+            assert_eq!(governance.reload_storage(), Ok(()));
+            // The stored copy wins over changes that were never written back
+            assert_eq!(governance.proposal_count(), 0);
+
+            ink::env::clear_contract_storage(&key);
+            assert_eq!(governance.reload_storage(), Err(CallFailure::StorageNotReloaded));
+        }
+
+        #[ink::test]
+        fn ai_contributions_saturate_instead_of_overflowing() {
+            let accounts = accounts();
+            let mut governance = governance();
+            set_caller(accounts.bob);
+            assert_eq!(governance.update_ai_contribution(accounts.charlie, u32::MAX), Ok(()));
+            assert_eq!(governance.update_ai_contribution(accounts.charlie, 1), Ok(()));
+
+            let profile = governance.get_citizen_profile(accounts.charlie);
+            assert_eq!((profile.ai_contributions, profile.reputation_score), (u32::MAX, u32::MAX));
+            assert_eq!(profile.voting_power, u32::MAX / 100);
+            // Further rewards keep the score pinned
+            let proposal_id = propose(&mut governance, accounts.charlie);
+            assert_eq!(governance.get_citizen_profile(accounts.charlie).reputation_score, u32::MAX);
+            set_caller(accounts.charlie);
+            assert_eq!(governance.vote(proposal_id, VoteType::For), Ok(()));
+            assert_eq!(governance.get_citizen_profile(accounts.charlie).reputation_score, u32::MAX);
+        }
+    }
+}
@@ -0,0 +1,532 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod city_token {
+    use city_common::psp22::{PSP22Error, PSP22Metadata, PSP22Votes, PSP22};
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    /// Prefix of every signed permit, so the signature cannot be replayed as
+    /// anything else.
+    pub const PERMIT_DOMAIN: [u8; 12] = *b"city-permit:";
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        PSP22(PSP22Error),
+        Unauthorized,
+        CapExceeded,
+        PermitExpired,
+        InvalidSignature,
+    }
+
+    impl From<PSP22Error> for Error {
+        fn from(error: PSP22Error) -> Self {
+            Error::PSP22(error)
+        }
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Whose history a checkpoint belongs to: an account, or the total
+    /// supply for `None`.
+    pub type Holder = Option<AccountId>;
+
+    /// A balance, or the total supply, held from `block` onwards.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Checkpoint {
+        pub block: BlockNumber,
+        pub value: Balance,
+    }
+
+    #[ink(storage)]
+    pub struct CityToken {
+        balances: Mapping<AccountId, Balance>,
+        allowances: Mapping<(AccountId, AccountId), Balance>, // (owner, spender) -> allowance
+        nonces: Mapping<AccountId, u64>,
+        checkpoints: Mapping<(Holder, u32), Checkpoint>, // (holder, index) -> checkpoint
+        checkpoint_counts: Mapping<Holder, u32>,
+        total_supply: Balance,
+        cap: Balance,
+        name: Option<String>,
+        symbol: Option<String>,
+        decimals: u8,
+        governance: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        amount: Balance,
+    }
+
+    impl CityToken {
+        /// Creates the token with no supply; every token is minted through
+        /// governance.
+        #[ink(constructor)]
+        pub fn new(
+            governance: AccountId,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+            cap: Balance,
+        ) -> Self {
+            Self {
+                balances: Mapping::default(),
+                allowances: Mapping::default(),
+                nonces: Mapping::default(),
+                checkpoints: Mapping::default(),
+                checkpoint_counts: Mapping::default(),
+                total_supply: 0,
+                cap,
+                name,
+                symbol,
+                decimals,
+                governance,
+            }
+        }
+
+        /// Most tokens that can ever be in circulation.
+        #[ink(message)]
+        pub fn cap(&self) -> Balance {
+            self.cap
+        }
+
+        /// Creates `value` tokens for `to`, up to the cap. Only reachable
+        /// through an executed governance proposal.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.ensure_governance()?;
+            if value > self.cap - self.total_supply {
+                return Err(Error::CapExceeded);
+            }
+
+            self.set_total_supply(self.total_supply + value);
+            self.set_balance(to, self.balance_of(to) + value);
+
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Destroys `value` tokens of `from`. Only reachable through an
+        /// executed governance proposal; burning another account's tokens
+        /// uses up their allowance to governance.
+        #[ink(message)]
+        pub fn burn(&mut self, from: AccountId, value: Balance) -> Result<()> {
+            self.ensure_governance()?;
+            if from != self.governance {
+                self.spend_allowance(from, self.governance, value)?;
+            }
+            let balance = self.balance_of(from);
+            if value > balance {
+                return Err(PSP22Error::InsufficientBalance.into());
+            }
+
+            self.set_balance(from, balance - value);
+            self.set_total_supply(self.total_supply - value);
+
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: None,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Sets the allowance of `spender` over `owner`'s tokens to `value`
+        /// on the strength of `owner`'s ECDSA signature, so the owner needs no
+        /// transaction of their own. The signed message is the permit payload
+        /// whose hash `get_permit_hash` returns.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired);
+            }
+
+            let hash = self.get_permit_hash(owner, spender, value, deadline);
+            let public_key = self.env().ecdsa_recover(&signature, &hash).map_err(|_| Error::InvalidSignature)?;
+            let mut signer = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&public_key, &mut signer);
+            if AccountId::from(signer) != owner {
+                return Err(Error::InvalidSignature);
+            }
+
+            self.nonces.insert(owner, &(self.nonces(owner) + 1));
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
+
+        /// Blake2-256 hash of the permit payload `owner` has to sign, using
+        /// their next nonce.
+        #[ink(message)]
+        pub fn get_permit_hash(&self, owner: AccountId, spender: AccountId, value: Balance, deadline: Timestamp) -> [u8; 32] {
+            let payload = (
+                PERMIT_DOMAIN,
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                self.nonces(owner),
+                deadline,
+            );
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(&payload, &mut output);
+            output
+        }
+
+        /// Permits of `owner` used so far.
+        #[ink(message)]
+        pub fn nonces(&self, owner: AccountId) -> u64 {
+            self.nonces.get(owner).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_governance(&self) -> AccountId {
+            self.governance
+        }
+
+        fn ensure_governance(&self) -> Result<()> {
+            if self.env().caller() != self.governance {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+
+        fn transfer_from_to(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            let from_balance = self.balance_of(from);
+            if value > from_balance {
+                return Err(PSP22Error::InsufficientBalance);
+            }
+
+            self.set_balance(from, from_balance - value);
+            self.set_balance(to, self.balance_of(to) + value);
+
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+
+            Ok(())
+        }
+
+        fn set_balance(&mut self, owner: AccountId, balance: Balance) {
+            self.balances.insert(owner, &balance);
+            self.write_checkpoint(Some(owner), balance);
+        }
+
+        fn set_total_supply(&mut self, total_supply: Balance) {
+            self.total_supply = total_supply;
+            self.write_checkpoint(None, total_supply);
+        }
+
+        /// Records `value` for `holder` from the current block on,
+        /// overwriting a checkpoint already written in this block.
+        fn write_checkpoint(&mut self, holder: Holder, value: Balance) {
+            let block = self.env().block_number();
+            let count = self.checkpoint_counts.get(holder).unwrap_or(0);
+
+            if count > 0 {
+                if let Some(last) = self.checkpoints.get((holder, count - 1)) {
+                    if last.block == block {
+                        self.checkpoints.insert((holder, count - 1), &Checkpoint { block, value });
+                        return;
+                    }
+                }
+            }
+
+            self.checkpoints.insert((holder, count), &Checkpoint { block, value });
+            self.checkpoint_counts.insert(holder, &(count + 1));
+        }
+
+        /// Value of the last checkpoint of `holder` strictly before `block`.
+        fn checkpoint_before(&self, holder: Holder, block: BlockNumber) -> Balance {
+            let (mut low, mut high) = (0, self.checkpoint_counts.get(holder).unwrap_or(0));
+            while low < high {
+                let mid = low + (high - low) / 2;
+                match self.checkpoints.get((holder, mid)) {
+                    Some(checkpoint) if checkpoint.block < block => low = mid + 1,
+                    _ => high = mid,
+                }
+            }
+
+            if low == 0 {
+                return 0;
+            }
+            self.checkpoints
+                .get((holder, low - 1))
+                .map(|checkpoint| checkpoint.value)
+                .unwrap_or_default()
+        }
+
+        fn set_allowance(&mut self, owner: AccountId, spender: AccountId, amount: Balance) {
+            self.allowances.insert((owner, spender), &amount);
+            self.env().emit_event(Approval { owner, spender, amount });
+        }
+
+        fn spend_allowance(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            let allowance = self.allowance(owner, spender);
+            if value > allowance {
+                return Err(PSP22Error::InsufficientAllowance);
+            }
+            self.set_allowance(owner, spender, allowance - value);
+            Ok(())
+        }
+    }
+
+    impl PSP22 for CityToken {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, _data: Vec<u8>) -> core::result::Result<(), PSP22Error> {
+            let from = self.env().caller();
+            self.transfer_from_to(from, to, value)
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22Error> {
+            let spender = self.env().caller();
+            if spender != from {
+                self.spend_allowance(from, spender, value)?;
+            }
+            self.transfer_from_to(from, to, value)
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> core::result::Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> core::result::Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self.allowance(owner, spender);
+            self.set_allowance(owner, spender, allowance.saturating_add(delta_value));
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> core::result::Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self.allowance(owner, spender);
+            if delta_value > allowance {
+                return Err(PSP22Error::InsufficientAllowance);
+            }
+            self.set_allowance(owner, spender, allowance - delta_value);
+            Ok(())
+        }
+    }
+
+    impl PSP22Votes for CityToken {
+        #[ink(message)]
+        fn balance_of_at(&self, owner: AccountId, block: BlockNumber) -> Balance {
+            self.checkpoint_before(Some(owner), block)
+        }
+
+        #[ink(message)]
+        fn total_supply_at(&self, block: BlockNumber) -> Balance {
+            self.checkpoint_before(None, block)
+        }
+    }
+
+    impl PSP22Metadata for CityToken {
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            self.name.clone()
+        }
+
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            self.symbol.clone()
+        }
+
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            self.decimals
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+        use secp256k1::{Message, PublicKey, SecretKey, SECP256K1};
+
+        const CAP: Balance = 1_000;
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        /// Token governed by Alice, the default caller.
+        fn token() -> CityToken {
+            CityToken::new(accounts().alice, Some(String::from("City")), Some(String::from("CITY")), 12, CAP)
+        }
+
+        /// A secp256k1 key made from `seed` and the account it controls.
+        fn keypair(seed: u8) -> (SecretKey, AccountId) {
+            let secret = SecretKey::from_slice(&[seed; 32]).unwrap();
+            let public_key = PublicKey::from_secret_key(SECP256K1, &secret).serialize();
+            let mut account = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&public_key, &mut account);
+            (secret, AccountId::from(account))
+        }
+
+        fn sign(secret: &SecretKey, hash: [u8; 32]) -> [u8; 65] {
+            let message = Message::from_slice(&hash).unwrap();
+            let (recovery_id, compact) = SECP256K1.sign_ecdsa_recoverable(&message, secret).serialize_compact();
+            let mut signature = [0; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            signature
+        }
+
+        #[ink::test]
+        fn mint_is_governance_only_and_capped() {
+            let accounts = accounts();
+            let mut token = token();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(token.mint(accounts.bob, 1), Err(Error::Unauthorized));
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(token.mint(accounts.bob, 600), Ok(()));
+            assert_eq!(token.mint(accounts.charlie, 401), Err(Error::CapExceeded));
+            assert_eq!(token.mint(accounts.charlie, 400), Ok(()));
+            assert_eq!(token.total_supply(), CAP);
+        }
+
+        #[ink::test]
+        fn balances_are_checkpointed_per_block() {
+            let accounts = accounts();
+            let mut token = token();
+            assert_eq!(token.mint(accounts.bob, 100), Ok(()));
+            test::advance_block::<DefaultEnvironment>();
+
+            // A snapshot taken at block 1 is not moved by later transfers
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(token.transfer(accounts.charlie, 40, Vec::new()), Ok(()));
+            assert_eq!(token.transfer(accounts.charlie, 10, Vec::new()), Ok(()));
+            test::advance_block::<DefaultEnvironment>();
+
+            assert_eq!(token.balance_of_at(accounts.bob, 0), 0);
+            assert_eq!(token.balance_of_at(accounts.bob, 1), 100);
+            assert_eq!(token.balance_of_at(accounts.charlie, 1), 0);
+            assert_eq!(token.balance_of_at(accounts.bob, 2), 50);
+            assert_eq!(token.balance_of_at(accounts.charlie, 2), 50);
+            assert_eq!(token.total_supply_at(1), 100);
+            // Both transfers of block 1 share one checkpoint
+            assert_eq!(token.checkpoint_counts.get(Some(accounts.bob)), Some(2));
+        }
+
+        #[ink::test]
+        fn permits_approve_once_per_nonce() {
+            let accounts = accounts();
+            let mut token = token();
+            let (secret, owner) = keypair(7);
+            let deadline = 100;
+
+            let signature = sign(&secret, token.get_permit_hash(owner, accounts.bob, 50, deadline));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(token.permit(owner, accounts.bob, 50, deadline, signature), Ok(()));
+            assert_eq!(token.allowance(owner, accounts.bob), 50);
+            assert_eq!(token.nonces(owner), 1);
+
+            // The nonce moved on, so the same signature no longer matches
+            assert_eq!(
+                token.permit(owner, accounts.bob, 50, deadline, signature),
+                Err(Error::InvalidSignature)
+            );
+        }
+
+        #[ink::test]
+        fn permits_need_the_owner_before_the_deadline() {
+            let accounts = accounts();
+            let mut token = token();
+            let (secret, owner) = keypair(7);
+            let (other_secret, _) = keypair(8);
+
+            let hash = token.get_permit_hash(owner, accounts.bob, 50, 100);
+            assert_eq!(
+                token.permit(owner, accounts.bob, 50, 100, sign(&other_secret, hash)),
+                Err(Error::InvalidSignature)
+            );
+            // A valid signature cannot be stretched to a larger allowance
+            assert_eq!(
+                token.permit(owner, accounts.bob, 60, 100, sign(&secret, hash)),
+                Err(Error::InvalidSignature)
+            );
+
+            test::set_block_timestamp::<DefaultEnvironment>(101);
+            assert_eq!(
+                token.permit(owner, accounts.bob, 50, 100, sign(&secret, hash)),
+                Err(Error::PermitExpired)
+            );
+            assert_eq!(token.allowance(owner, accounts.bob), 0);
+            assert_eq!(token.nonces(owner), 0);
+        }
+    }
+}
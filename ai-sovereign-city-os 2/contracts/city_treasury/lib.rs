@@ -0,0 +1,715 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod city_treasury {
+    use city_common::psp22::PSP22;
+    use city_common::treasury::{Asset, Treasury, TreasuryError, ZoneFunding};
+    use city_common::{ProposalId, ZoneId};
+    use ink::contract_ref;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    /// Upper bound on the number of tranches of a single reservation.
+    pub const MAX_TRANCHES: u32 = 16;
+
+    pub type Result<T> = core::result::Result<T, TreasuryError>;
+
+    /// Funds set aside for a passed proposal, paid out tranche by tranche.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Reservation {
+        pub asset: Asset,
+        pub beneficiary: AccountId,
+        pub tranches: Vec<Balance>,
+        /// Index of the next tranche to pay.
+        pub next_tranche: u32,
+        pub released: Balance,
+    }
+
+    impl Reservation {
+        fn remaining(&self) -> Balance {
+            self.tranches.iter().sum::<Balance>() - self.released
+        }
+    }
+
+    /// A budget paid out linearly over time rather than in tranches.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Stream {
+        pub asset: Asset,
+        pub recipient: AccountId,
+        pub total: Balance,
+        pub start: Timestamp,
+        /// Nothing can be withdrawn before this time, though it accrues.
+        pub cliff: Timestamp,
+        pub end: Timestamp,
+        pub withdrawn: Balance,
+    }
+
+    impl Stream {
+        /// Amount vested by `now`, ignoring the cliff.
+        fn vested(&self, now: Timestamp) -> Balance {
+            if now >= self.end {
+                return self.total;
+            }
+            let elapsed = now.saturating_sub(self.start) as Balance;
+            let duration = (self.end - self.start) as Balance;
+            // Split to keep `total * elapsed` from overflowing
+            self.total / duration * elapsed + self.total % duration * elapsed / duration
+        }
+
+        fn claimable(&self, now: Timestamp) -> Balance {
+            if now < self.cliff {
+                return 0;
+            }
+            self.vested(now) - self.withdrawn
+        }
+    }
+
+    #[ink(storage)]
+    pub struct CityTreasury {
+        reservations: Mapping<ProposalId, Reservation>,
+        streams: Mapping<ProposalId, Stream>,
+        reserved: Mapping<Asset, Balance>,
+        /// Zone treasury allowed to draw zone budgets.
+        zone_treasury: Option<AccountId>,
+        zone_draws: Mapping<(ZoneId, u64), Balance>, // (zone, period) -> amount drawn
+        governance: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Deposited {
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct FundsReserved {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        asset: Asset,
+        beneficiary: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TrancheReleased {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        beneficiary: AccountId,
+        tranche: u32,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ReservationCancelled {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        returned: Balance,
+    }
+
+    #[ink(event)]
+    pub struct StreamStarted {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        recipient: AccountId,
+        total: Balance,
+        start: Timestamp,
+        cliff: Timestamp,
+        end: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct StreamWithdrawn {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct StreamCancelled {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        paid: Balance,
+        returned: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ZoneBudgetDrawn {
+        #[ink(topic)]
+        zone_id: ZoneId,
+        period: u64,
+        #[ink(topic)]
+        recipient: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct FundsTransferred {
+        #[ink(topic)]
+        to: AccountId,
+        asset: Asset,
+        amount: Balance,
+    }
+
+    impl CityTreasury {
+        #[ink(constructor, payable)]
+        pub fn new(governance: AccountId) -> Self {
+            Self {
+                reservations: Mapping::default(),
+                streams: Mapping::default(),
+                reserved: Mapping::default(),
+                zone_treasury: None,
+                zone_draws: Mapping::default(),
+                governance,
+            }
+        }
+
+        /// Adds native funds to the treasury. PSP22 tokens are deposited by
+        /// transferring them to the treasury account.
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) {
+            self.env().emit_event(Deposited {
+                from: self.env().caller(),
+                amount: self.env().transferred_value(),
+            });
+        }
+
+        /// Spends unreserved funds outside the proposal budget flow.
+        #[ink(message)]
+        pub fn transfer(&mut self, asset: Asset, to: AccountId, amount: Balance) -> Result<()> {
+            self.ensure_governance()?;
+            if amount > self.available_balance(asset) {
+                return Err(TreasuryError::InsufficientFunds);
+            }
+
+            self.pay(asset, to, amount)?;
+            self.env().emit_event(FundsTransferred { to, asset, amount });
+            Ok(())
+        }
+
+        /// Pays the recipient of a stream everything vested and not yet
+        /// withdrawn.
+        #[ink(message)]
+        pub fn withdraw(&mut self, proposal_id: ProposalId) -> Result<Balance> {
+            let mut stream = self.streams.get(proposal_id).ok_or(TreasuryError::StreamNotFound)?;
+            if self.env().caller() != stream.recipient {
+                return Err(TreasuryError::Unauthorized);
+            }
+
+            let amount = stream.claimable(self.env().block_timestamp());
+            if amount == 0 {
+                return Ok(0);
+            }
+            stream.withdrawn += amount;
+            self.settle_stream(proposal_id, &stream);
+            self.unreserve(stream.asset, amount);
+            self.pay(stream.asset, stream.recipient, amount)?;
+
+            self.env().emit_event(StreamWithdrawn {
+                proposal_id,
+                recipient: stream.recipient,
+                amount,
+            });
+
+            Ok(amount)
+        }
+
+        /// Stops a stream. What has vested past the cliff is paid to the
+        /// recipient and the rest returns to the available balance.
+        #[ink(message)]
+        pub fn cancel_stream(&mut self, proposal_id: ProposalId) -> Result<Balance> {
+            self.ensure_governance()?;
+            let stream = self.streams.take(proposal_id).ok_or(TreasuryError::StreamNotFound)?;
+
+            let paid = stream.claimable(self.env().block_timestamp());
+            let returned = stream.total - stream.withdrawn - paid;
+            self.unreserve(stream.asset, paid + returned);
+            if paid > 0 {
+                self.pay(stream.asset, stream.recipient, paid)?;
+            }
+
+            self.env().emit_event(StreamCancelled {
+                proposal_id,
+                paid,
+                returned,
+            });
+
+            Ok(returned)
+        }
+
+        #[ink(message)]
+        pub fn get_stream(&self, proposal_id: ProposalId) -> Option<Stream> {
+            self.streams.get(proposal_id)
+        }
+
+        /// Amount of a stream vested so far, withdrawn or not.
+        #[ink(message)]
+        pub fn accrued(&self, proposal_id: ProposalId) -> Balance {
+            self.streams
+                .get(proposal_id)
+                .map(|stream| stream.vested(self.env().block_timestamp()))
+                .unwrap_or(0)
+        }
+
+        /// Amount the recipient of a stream could withdraw now.
+        #[ink(message)]
+        pub fn claimable(&self, proposal_id: ProposalId) -> Balance {
+            self.streams
+                .get(proposal_id)
+                .map(|stream| stream.claimable(self.env().block_timestamp()))
+                .unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_reservation(&self, proposal_id: ProposalId) -> Option<Reservation> {
+            self.reservations.get(proposal_id)
+        }
+
+        #[ink(message)]
+        pub fn get_reserved(&self, asset: Asset) -> Balance {
+            self.reserved.get(asset).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_governance(&self) -> AccountId {
+            self.governance
+        }
+
+        /// Allows a zone treasury to draw zone budgets, or stops all draws
+        /// with `None`.
+        #[ink(message)]
+        pub fn set_zone_treasury(&mut self, zone_treasury: Option<AccountId>) -> Result<()> {
+            self.ensure_governance()?;
+            self.zone_treasury = zone_treasury;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_zone_treasury(&self) -> Option<AccountId> {
+            self.zone_treasury
+        }
+
+        /// Amount drawn as the budget of a zone for a period, if any.
+        #[ink(message)]
+        pub fn get_zone_draw(&self, zone_id: ZoneId, period: u64) -> Option<Balance> {
+            self.zone_draws.get((zone_id, period))
+        }
+
+        fn ensure_governance(&self) -> Result<()> {
+            if self.env().caller() != self.governance {
+                return Err(TreasuryError::Unauthorized);
+            }
+            Ok(())
+        }
+
+        fn balance(&self, asset: Asset) -> Balance {
+            match asset {
+                Asset::Native => self.env().balance(),
+                Asset::Token(token) => {
+                    let token: contract_ref!(PSP22) = token.into();
+                    token.balance_of(self.env().account_id())
+                }
+            }
+        }
+
+        fn available_balance(&self, asset: Asset) -> Balance {
+            self.balance(asset).saturating_sub(self.reserved.get(asset).unwrap_or(0))
+        }
+
+        fn pay(&mut self, asset: Asset, to: AccountId, amount: Balance) -> Result<()> {
+            match asset {
+                Asset::Native => self.env().transfer(to, amount).map_err(|_| TreasuryError::TransferFailed),
+                Asset::Token(token) => {
+                    let mut token: contract_ref!(PSP22) = token.into();
+                    token.transfer(to, amount, Vec::new()).map_err(|_| TreasuryError::TransferFailed)
+                }
+            }
+        }
+
+        /// Stores a stream, dropping it once fully withdrawn.
+        fn settle_stream(&mut self, proposal_id: ProposalId, stream: &Stream) {
+            if stream.withdrawn == stream.total {
+                self.streams.remove(proposal_id);
+            } else {
+                self.streams.insert(proposal_id, stream);
+            }
+        }
+
+        fn unreserve(&mut self, asset: Asset, amount: Balance) {
+            let reserved = self.reserved.get(asset).unwrap_or(0);
+            self.reserved.insert(asset, &reserved.saturating_sub(amount));
+        }
+    }
+
+    impl Treasury for CityTreasury {
+        #[ink(message)]
+        fn available(&self, asset: Asset) -> Balance {
+            self.available_balance(asset)
+        }
+
+        #[ink(message)]
+        fn reserve(
+            &mut self,
+            proposal_id: ProposalId,
+            asset: Asset,
+            beneficiary: AccountId,
+            tranches: Vec<Balance>,
+        ) -> Result<()> {
+            self.ensure_governance()?;
+            if self.reservations.contains(proposal_id) {
+                return Err(TreasuryError::ReservationExists);
+            }
+            if tranches.is_empty() || tranches.len() > MAX_TRANCHES as usize {
+                return Err(TreasuryError::InvalidTranches);
+            }
+
+            let amount: Balance = tranches.iter().sum();
+            if amount > self.available_balance(asset) {
+                return Err(TreasuryError::InsufficientFunds);
+            }
+
+            let reserved = self.reserved.get(asset).unwrap_or(0);
+            self.reserved.insert(asset, &(reserved + amount));
+            self.reservations.insert(
+                proposal_id,
+                &Reservation {
+                    asset,
+                    beneficiary,
+                    tranches,
+                    next_tranche: 0,
+                    released: 0,
+                },
+            );
+
+            self.env().emit_event(FundsReserved {
+                proposal_id,
+                asset,
+                beneficiary,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn release_next_tranche(&mut self, proposal_id: ProposalId) -> Result<Balance> {
+            self.ensure_governance()?;
+            let mut reservation = self.reservations.get(proposal_id).ok_or(TreasuryError::ReservationNotFound)?;
+            let tranche = reservation.next_tranche;
+            let amount = *reservation.tranches.get(tranche as usize).ok_or(TreasuryError::NoTranchesLeft)?;
+
+            reservation.next_tranche += 1;
+            reservation.released += amount;
+            self.reservations.insert(proposal_id, &reservation);
+            self.unreserve(reservation.asset, amount);
+            self.pay(reservation.asset, reservation.beneficiary, amount)?;
+
+            self.env().emit_event(TrancheReleased {
+                proposal_id,
+                beneficiary: reservation.beneficiary,
+                tranche,
+                amount,
+            });
+
+            Ok(amount)
+        }
+
+        #[ink(message)]
+        fn stream_reservation(
+            &mut self,
+            proposal_id: ProposalId,
+            start: Timestamp,
+            cliff: Timestamp,
+            end: Timestamp,
+        ) -> Result<Balance> {
+            self.ensure_governance()?;
+            if !(start <= cliff && cliff <= end && start < end) {
+                return Err(TreasuryError::InvalidSchedule);
+            }
+            let reservation = self.reservations.take(proposal_id).ok_or(TreasuryError::ReservationNotFound)?;
+
+            // Funds stay reserved until withdrawn or the stream is cancelled
+            let total = reservation.remaining();
+            self.streams.insert(
+                proposal_id,
+                &Stream {
+                    asset: reservation.asset,
+                    recipient: reservation.beneficiary,
+                    total,
+                    start,
+                    cliff,
+                    end,
+                    withdrawn: 0,
+                },
+            );
+
+            self.env().emit_event(StreamStarted {
+                proposal_id,
+                recipient: reservation.beneficiary,
+                total,
+                start,
+                cliff,
+                end,
+            });
+
+            Ok(total)
+        }
+
+        #[ink(message)]
+        fn cancel_reservation(&mut self, proposal_id: ProposalId) -> Result<Balance> {
+            self.ensure_governance()?;
+            let reservation = self.reservations.take(proposal_id).ok_or(TreasuryError::ReservationNotFound)?;
+            let returned = reservation.remaining();
+            self.unreserve(reservation.asset, returned);
+
+            self.env().emit_event(ReservationCancelled { proposal_id, returned });
+
+            Ok(returned)
+        }
+    }
+
+    impl ZoneFunding for CityTreasury {
+        /// Zone budgets come out of unreserved funds, so they never touch
+        /// money set aside for proposals.
+        #[ink(message)]
+        fn draw_zone_budget(
+            &mut self,
+            zone_id: ZoneId,
+            period: u64,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            if self.zone_treasury != Some(self.env().caller()) {
+                return Err(TreasuryError::Unauthorized);
+            }
+            if self.zone_draws.contains((zone_id, period)) {
+                return Err(TreasuryError::AlreadyDrawn);
+            }
+            if amount > self.available_balance(Asset::Native) {
+                return Err(TreasuryError::InsufficientFunds);
+            }
+
+            self.zone_draws.insert((zone_id, period), &amount);
+            self.pay(Asset::Native, recipient, amount)?;
+
+            self.env().emit_event(ZoneBudgetDrawn {
+                zone_id,
+                period,
+                recipient,
+                amount,
+            });
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        const FUNDS: Balance = 1_000;
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        fn balance_of(account: AccountId) -> Balance {
+            test::get_account_balance::<DefaultEnvironment>(account).unwrap_or(0)
+        }
+
+        /// Treasury governed by Alice, holding `FUNDS` of the native asset.
+        fn treasury() -> CityTreasury {
+            set_caller(accounts().alice);
+            test::set_account_balance::<DefaultEnvironment>(test::callee::<DefaultEnvironment>(), FUNDS);
+            CityTreasury::new(accounts().alice)
+        }
+
+        #[ink::test]
+        fn reservations_are_limited_to_unreserved_funds() {
+            let accounts = accounts();
+            let mut treasury = treasury();
+
+            assert_eq!(treasury.reserve(0, Asset::Native, accounts.bob, vec![300, 300]), Ok(()));
+            assert_eq!(treasury.available(Asset::Native), 400);
+            assert_eq!(
+                treasury.reserve(1, Asset::Native, accounts.bob, vec![401]),
+                Err(TreasuryError::InsufficientFunds)
+            );
+            assert_eq!(
+                treasury.reserve(0, Asset::Native, accounts.bob, vec![1]),
+                Err(TreasuryError::ReservationExists)
+            );
+            assert_eq!(
+                treasury.reserve(1, Asset::Native, accounts.bob, Vec::new()),
+                Err(TreasuryError::InvalidTranches)
+            );
+            assert_eq!(
+                treasury.transfer(Asset::Native, accounts.charlie, 401),
+                Err(TreasuryError::InsufficientFunds)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                treasury.reserve(1, Asset::Native, accounts.bob, vec![1]),
+                Err(TreasuryError::Unauthorized)
+            );
+        }
+
+        #[ink::test]
+        fn tranches_are_released_in_order() {
+            let accounts = accounts();
+            let mut treasury = treasury();
+            let bob_before = balance_of(accounts.bob);
+            assert_eq!(treasury.reserve(0, Asset::Native, accounts.bob, vec![100, 250]), Ok(()));
+
+            assert_eq!(treasury.release_next_tranche(0), Ok(100));
+            assert_eq!(treasury.release_next_tranche(0), Ok(250));
+            assert_eq!(treasury.release_next_tranche(0), Err(TreasuryError::NoTranchesLeft));
+
+            assert_eq!(balance_of(accounts.bob), bob_before + 350);
+            assert_eq!(treasury.get_reserved(Asset::Native), 0);
+            assert_eq!(treasury.available(Asset::Native), FUNDS - 350);
+        }
+
+        #[ink::test]
+        fn cancelling_returns_the_unreleased_tranches() {
+            let accounts = accounts();
+            let mut treasury = treasury();
+            assert_eq!(treasury.reserve(0, Asset::Native, accounts.bob, vec![100, 200, 300]), Ok(()));
+            assert_eq!(treasury.release_next_tranche(0), Ok(100));
+
+            assert_eq!(treasury.cancel_reservation(0), Ok(500));
+            assert_eq!(treasury.get_reservation(0), None);
+            assert_eq!(treasury.available(Asset::Native), FUNDS - 100);
+            assert_eq!(treasury.cancel_reservation(0), Err(TreasuryError::ReservationNotFound));
+        }
+
+        /// Streams 1000 to Bob from time 100 to 1100, with the cliff at 300.
+        fn streaming_treasury() -> CityTreasury {
+            let mut treasury = treasury();
+            assert_eq!(treasury.reserve(0, Asset::Native, accounts().bob, vec![FUNDS]), Ok(()));
+            assert_eq!(treasury.stream_reservation(0, 100, 300, 1_100), Ok(FUNDS));
+            treasury
+        }
+
+        #[ink::test]
+        fn streams_vest_linearly_behind_the_cliff() {
+            let accounts = accounts();
+            let mut treasury = streaming_treasury();
+            assert_eq!(treasury.get_reservation(0), None);
+
+            test::set_block_timestamp::<DefaultEnvironment>(250);
+            assert_eq!((treasury.accrued(0), treasury.claimable(0)), (150, 0));
+            set_caller(accounts.bob);
+            assert_eq!(treasury.withdraw(0), Ok(0));
+
+            test::set_block_timestamp::<DefaultEnvironment>(600);
+            assert_eq!(treasury.withdraw(0), Ok(500));
+            assert_eq!((treasury.accrued(0), treasury.claimable(0)), (500, 0));
+
+            test::set_block_timestamp::<DefaultEnvironment>(5_000);
+            assert_eq!(treasury.withdraw(0), Ok(500));
+            assert_eq!(treasury.get_stream(0), None);
+            assert_eq!(treasury.get_reserved(Asset::Native), 0);
+        }
+
+        #[ink::test]
+        fn only_the_recipient_withdraws() {
+            let accounts = accounts();
+            let mut treasury = streaming_treasury();
+            test::set_block_timestamp::<DefaultEnvironment>(600);
+            set_caller(accounts.charlie);
+            assert_eq!(treasury.withdraw(0), Err(TreasuryError::Unauthorized));
+        }
+
+        #[ink::test]
+        fn cancelled_streams_pay_what_vested_and_return_the_rest() {
+            let accounts = accounts();
+            let mut treasury = streaming_treasury();
+            let bob_before = balance_of(accounts.bob);
+
+            test::set_block_timestamp::<DefaultEnvironment>(400);
+            set_caller(accounts.bob);
+            assert_eq!(treasury.withdraw(0), Ok(300));
+            assert_eq!(treasury.cancel_stream(0), Err(TreasuryError::Unauthorized));
+
+            test::set_block_timestamp::<DefaultEnvironment>(500);
+            set_caller(accounts.alice);
+            assert_eq!(treasury.cancel_stream(0), Ok(600));
+            assert_eq!(balance_of(accounts.bob), bob_before + 400);
+            assert_eq!(treasury.get_reserved(Asset::Native), 0);
+            assert_eq!(treasury.available(Asset::Native), FUNDS - 400);
+        }
+
+        #[ink::test]
+        fn vesting_large_totals_does_not_overflow() {
+            let stream = Stream {
+                asset: Asset::Native,
+                recipient: accounts().bob,
+                total: Balance::MAX,
+                start: 0,
+                cliff: 0,
+                end: 3,
+                withdrawn: 0,
+            };
+            assert_eq!(stream.vested(1), Balance::MAX / 3);
+            assert_eq!(stream.vested(2), Balance::MAX / 3 * 2);
+            assert_eq!(stream.vested(3), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn streams_need_an_ordered_schedule() {
+            let mut treasury = treasury();
+            assert_eq!(treasury.reserve(0, Asset::Native, accounts().bob, vec![FUNDS]), Ok(()));
+            assert_eq!(treasury.stream_reservation(0, 100, 50, 200), Err(TreasuryError::InvalidSchedule));
+            assert_eq!(treasury.stream_reservation(0, 100, 100, 100), Err(TreasuryError::InvalidSchedule));
+            assert_eq!(treasury.stream_reservation(1, 0, 0, 100), Err(TreasuryError::ReservationNotFound));
+        }
+
+        #[ink::test]
+        fn the_zone_treasury_draws_each_zone_period_once() {
+            let accounts = accounts();
+            let mut treasury = treasury();
+            assert_eq!(treasury.reserve(0, Asset::Native, accounts.bob, vec![600]), Ok(()));
+
+            set_caller(accounts.django);
+            assert_eq!(treasury.draw_zone_budget(0, 0, accounts.eve, 100), Err(TreasuryError::Unauthorized));
+            assert_eq!(treasury.set_zone_treasury(Some(accounts.django)), Err(TreasuryError::Unauthorized));
+            set_caller(accounts.alice);
+            assert_eq!(treasury.set_zone_treasury(Some(accounts.django)), Ok(()));
+
+            set_caller(accounts.django);
+            let eve_before = balance_of(accounts.eve);
+            assert_eq!(treasury.draw_zone_budget(0, 0, accounts.eve, 100), Ok(()));
+            assert_eq!(balance_of(accounts.eve), eve_before + 100);
+            assert_eq!(treasury.get_zone_draw(0, 0), Some(100));
+            assert_eq!(treasury.draw_zone_budget(0, 0, accounts.eve, 100), Err(TreasuryError::AlreadyDrawn));
+
+            // Funds reserved for proposals stay out of reach
+            assert_eq!(
+                treasury.draw_zone_budget(1, 0, accounts.eve, 301),
+                Err(TreasuryError::InsufficientFunds)
+            );
+            assert_eq!(treasury.draw_zone_budget(1, 0, accounts.eve, 300), Ok(()));
+            assert_eq!(treasury.draw_zone_budget(0, 1, accounts.eve, 0), Ok(()));
+        }
+    }
+}
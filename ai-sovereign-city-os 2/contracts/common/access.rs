@@ -0,0 +1,145 @@
+//! Role-based access control shared by the contracts.
+//!
+//! A contract keeps an [`AccessControl`] in its storage and implements the
+//! [`AccessControlled`] trait by delegating to it, emitting its own
+//! `RoleGranted`/`RoleRevoked` and ownership events. The owner administers
+//! all roles; ownership moves in two steps so it can be handed to the
+//! governance contract, which accepts it through an executed proposal.
+
+use crate::AccountId;
+use ink::storage::Mapping;
+
+pub type RoleId = u32;
+
+/// May register agents and change their status.
+pub const AGENT_MANAGER: RoleId = 1;
+/// May replace the AI oracle a contract listens to, alongside governance.
+/// No deployer holds it; the owner grants it deliberately.
+pub const ORACLE_ADMIN: RoleId = 2;
+/// May wire up and manage treasury funds.
+pub const TREASURER: RoleId = 3;
+/// May pause contract functions.
+pub const PAUSER: RoleId = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum AccessError {
+    MissingRole,
+    NotOwner,
+    NotPendingOwner,
+}
+
+#[ink::storage_item]
+#[derive(Debug)]
+pub struct AccessControl {
+    owner: AccountId,
+    pending_owner: Option<AccountId>,
+    roles: Mapping<(RoleId, AccountId), ()>,
+}
+
+impl AccessControl {
+    pub fn new(owner: AccountId) -> Self {
+        Self {
+            owner,
+            pending_owner: None,
+            roles: Mapping::default(),
+        }
+    }
+
+    pub fn owner(&self) -> AccountId {
+        self.owner
+    }
+
+    pub fn pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner
+    }
+
+    pub fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+        self.roles.contains((role, account))
+    }
+
+    pub fn ensure_role(&self, role: RoleId, account: AccountId) -> Result<(), AccessError> {
+        if !self.has_role(role, account) {
+            return Err(AccessError::MissingRole);
+        }
+        Ok(())
+    }
+
+    pub fn ensure_owner(&self, account: AccountId) -> Result<(), AccessError> {
+        if account != self.owner {
+            return Err(AccessError::NotOwner);
+        }
+        Ok(())
+    }
+
+    /// Gives `role` to `account` without an ownership check; meant for
+    /// constructors. Returns whether the account did not hold it yet.
+    pub fn set_role(&mut self, role: RoleId, account: AccountId) -> bool {
+        self.roles.insert((role, account), &()).is_none()
+    }
+
+    /// Returns whether the role was newly granted.
+    pub fn grant_role(&mut self, caller: AccountId, role: RoleId, account: AccountId) -> Result<bool, AccessError> {
+        self.ensure_owner(caller)?;
+        Ok(self.set_role(role, account))
+    }
+
+    /// Returns whether the account held the role.
+    pub fn revoke_role(&mut self, caller: AccountId, role: RoleId, account: AccountId) -> Result<bool, AccessError> {
+        self.ensure_owner(caller)?;
+        Ok(self.roles.take((role, account)).is_some())
+    }
+
+    /// Drops a role the caller holds.
+    pub fn renounce_role(&mut self, caller: AccountId, role: RoleId) -> Result<(), AccessError> {
+        self.roles.take((role, caller)).ok_or(AccessError::MissingRole)
+    }
+
+    /// Nominates `new_owner`, who takes over once they accept.
+    pub fn transfer_ownership(&mut self, caller: AccountId, new_owner: AccountId) -> Result<(), AccessError> {
+        self.ensure_owner(caller)?;
+        self.pending_owner = Some(new_owner);
+        Ok(())
+    }
+
+    /// Completes a transfer started by the owner. Returns the previous owner.
+    pub fn accept_ownership(&mut self, caller: AccountId) -> Result<AccountId, AccessError> {
+        if self.pending_owner != Some(caller) {
+            return Err(AccessError::NotPendingOwner);
+        }
+        self.pending_owner = None;
+        Ok(core::mem::replace(&mut self.owner, caller))
+    }
+}
+
+#[ink::trait_definition]
+pub trait AccessControlled {
+    #[ink(message)]
+    fn has_role(&self, role: RoleId, account: AccountId) -> bool;
+
+    /// Gives `role` to `account`. Only the owner may grant roles.
+    #[ink(message)]
+    fn grant_role(&mut self, role: RoleId, account: AccountId) -> Result<(), AccessError>;
+
+    /// Takes `role` from `account`. Only the owner may revoke roles.
+    #[ink(message)]
+    fn revoke_role(&mut self, role: RoleId, account: AccountId) -> Result<(), AccessError>;
+
+    /// Drops a role held by the caller.
+    #[ink(message)]
+    fn renounce_role(&mut self, role: RoleId) -> Result<(), AccessError>;
+
+    #[ink(message)]
+    fn owner(&self) -> AccountId;
+
+    #[ink(message)]
+    fn pending_owner(&self) -> Option<AccountId>;
+
+    /// Starts handing ownership to `new_owner`.
+    #[ink(message)]
+    fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), AccessError>;
+
+    /// Takes over ownership offered to the caller.
+    #[ink(message)]
+    fn accept_ownership(&mut self) -> Result<(), AccessError>;
+}
@@ -0,0 +1,10 @@
+//! Interface of the AI agent registry read by other contracts.
+
+use crate::ZoneId;
+
+#[ink::trait_definition]
+pub trait AgentStats {
+    /// Energy saved so far by all agents operating in `zone`.
+    #[ink(message)]
+    fn zone_energy_saved(&self, zone: ZoneId) -> u128;
+}
@@ -0,0 +1,86 @@
+//! Dispatching pre-encoded cross-contract calls, as done for executed
+//! proposals and accepted oracle attestations.
+
+use crate::{AccountId, Balance};
+use ink::env::call::{build_call, ExecutionInput, Selector};
+use ink::env::{CallFlags, DefaultEnvironment};
+use ink::prelude::vec::Vec;
+
+/// Why a dispatched call did not go through.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum CallFailure {
+    CalleeTrapped,
+    TransferFailed,
+    NotCallable,
+    /// The callee did not recognise the selector or could not decode the input.
+    InvalidInput,
+    /// The callee returned `Err`; holds the SCALE-encoded error value.
+    Reverted(Vec<u8>),
+    Other,
+    /// The call re-entered the caller, whose storage could not be read back
+    /// afterwards.
+    StorageNotReloaded,
+}
+
+/// Calls `selector` on `callee` with `input` appended as-is.
+///
+/// ink! messages that return `Err` revert and encode the `Err` variant
+/// (leading `0x01`), so calls are expected to target messages returning
+/// `()` or a `Result`.
+pub fn invoke(
+    callee: AccountId,
+    selector: [u8; 4],
+    input: &[u8],
+    transferred_value: Balance,
+    allow_reentry: bool,
+) -> Result<(), CallFailure> {
+    // Plain accounts have no messages to call
+    if !ink::env::is_contract::<DefaultEnvironment>(&callee) {
+        return Err(CallFailure::NotCallable);
+    }
+
+    let result = build_call::<DefaultEnvironment>()
+        .call(callee)
+        .transferred_value(transferred_value)
+        .call_flags(CallFlags::default().set_allow_reentry(allow_reentry))
+        .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(CallInput(input)))
+        .returns::<CallOutput>()
+        .try_invoke();
+
+    match result {
+        Ok(Ok(output)) => match output.0.split_first() {
+            Some((1, error)) => Err(CallFailure::Reverted(error.to_vec())),
+            _ => Ok(()),
+        },
+        Ok(Err(_)) => Err(CallFailure::InvalidInput),
+        Err(ink::env::Error::CalleeTrapped) => Err(CallFailure::CalleeTrapped),
+        Err(ink::env::Error::TransferFailed) => Err(CallFailure::TransferFailed),
+        Err(ink::env::Error::NotCallable) => Err(CallFailure::NotCallable),
+        Err(_) => Err(CallFailure::Other),
+    }
+}
+
+/// Pre-encoded call arguments, written to the input buffer verbatim.
+struct CallInput<'a>(&'a [u8]);
+
+impl scale::Encode for CallInput<'_> {
+    fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(self.0);
+    }
+}
+
+/// Raw return data of a dispatched call.
+struct CallOutput(Vec<u8>);
+
+impl scale::Decode for CallOutput {
+    fn decode<I: scale::Input>(input: &mut I) -> Result<Self, scale::Error> {
+        let len = input.remaining_len()?.unwrap_or(0);
+        let mut output = ink::prelude::vec![0u8; len];
+        input.read(&mut output)?;
+        Ok(Self(output))
+    }
+}
@@ -0,0 +1,15 @@
+//! Interface of the citizen registry that gates voting.
+
+use crate::{AccountId, BlockNumber};
+
+#[ink::trait_definition]
+pub trait CitizenRegistry {
+    /// Whether `account` holds an unexpired, unrevoked citizenship credential.
+    #[ink(message)]
+    fn is_citizen(&self, account: AccountId) -> bool;
+
+    /// Block since which `account` has held a valid credential for its
+    /// current identity without a break, or `None` if it holds none now.
+    #[ink(message)]
+    fn citizen_since(&self, account: AccountId) -> Option<BlockNumber>;
+}
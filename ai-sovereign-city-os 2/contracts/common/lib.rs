@@ -0,0 +1,24 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Types shared between the AI-Sovereign City OS contracts.
+
+pub mod access;
+pub mod agents;
+pub mod call;
+pub mod citizenship;
+pub mod pausable;
+pub mod psp22;
+pub mod treasury;
+pub mod zones;
+
+pub use ink::primitives::AccountId;
+
+pub type Balance = u128;
+pub type BlockNumber = u32;
+pub type Timestamp = u64;
+
+pub type ProposalId = u32;
+pub type AgentId = u32;
+pub type IncidentId = u32;
+
+pub type ZoneId = u32;
@@ -0,0 +1,112 @@
+//! Circuit breaker shared by the contracts.
+//!
+//! Functions are identified by the selector of the message they guard, e.g.
+//! `ink::selector_bytes!("vote")`. Accounts holding the `PAUSER` role can
+//! halt a function, or every guarded function at once with
+//! [`ALL_FUNCTIONS`]; lifting a pause early takes governance.
+//!
+//! Pauses lapse on their own once the window they were opened in closes, and
+//! pausers cannot open another window until a cooldown has passed. A single
+//! pauser key can therefore hold a contract for at most [`PAUSE_DURATION`],
+//! leaving citizens [`PAUSE_COOLDOWN`] to vote its role away.
+
+use crate::Timestamp;
+use ink::env::DefaultEnvironment;
+use ink::storage::{Lazy, Mapping};
+
+pub type FunctionId = [u8; 4];
+
+/// Pauses every guarded function of a contract.
+pub const ALL_FUNCTIONS: FunctionId = [0; 4];
+
+const DAY: Timestamp = 24 * 60 * 60 * 1_000;
+
+/// How long a pause window stays open, in milliseconds.
+pub const PAUSE_DURATION: Timestamp = 3 * DAY;
+/// How long after a window closes before pausers may open the next one.
+pub const PAUSE_COOLDOWN: Timestamp = 7 * DAY;
+
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PauseError {
+    Paused,
+    Unauthorized,
+    /// The last pause window closed less than [`PAUSE_COOLDOWN`] ago.
+    CoolingDown,
+}
+
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PauseState {
+    paused_until: Mapping<FunctionId, Timestamp>,
+    /// Start of the latest pause window.
+    window_opened_at: Lazy<Timestamp>,
+}
+
+impl PauseState {
+    pub fn is_paused(&self, function: FunctionId) -> bool {
+        let now = now();
+        [function, ALL_FUNCTIONS]
+            .into_iter()
+            .any(|function| self.paused_until.get(function).is_some_and(|until| until > now))
+    }
+
+    /// When the pause on `function` itself lapses, if it is paused.
+    pub fn paused_until(&self, function: FunctionId) -> Option<Timestamp> {
+        self.paused_until.get(function).filter(|until| *until > now())
+    }
+
+    pub fn ensure_not_paused(&self, function: FunctionId) -> Result<(), PauseError> {
+        if self.is_paused(function) {
+            return Err(PauseError::Paused);
+        }
+        Ok(())
+    }
+
+    /// Pauses `function` until the current window closes, opening a new
+    /// window if the cooldown has passed. Returns whether the function was
+    /// running until now.
+    pub fn pause(&mut self, function: FunctionId) -> Result<bool, PauseError> {
+        let now = now();
+        let opened_at = match self.window_opened_at.get() {
+            Some(opened_at) if now < opened_at + PAUSE_DURATION => opened_at,
+            Some(opened_at) if now < opened_at + PAUSE_DURATION + PAUSE_COOLDOWN => {
+                return Err(PauseError::CoolingDown)
+            }
+            _ => {
+                self.window_opened_at.set(&now);
+                now
+            }
+        };
+
+        let was_running = self.paused_until(function).is_none();
+        self.paused_until.insert(function, &(opened_at + PAUSE_DURATION));
+        Ok(was_running)
+    }
+
+    /// Returns whether the function was paused on its own. Functions stay
+    /// halted while [`ALL_FUNCTIONS`] is paused. Lifting a pause early does
+    /// not shorten the cooldown.
+    pub fn unpause(&mut self, function: FunctionId) -> bool {
+        self.paused_until.take(function).is_some_and(|until| until > now())
+    }
+}
+
+fn now() -> Timestamp {
+    ink::env::block_timestamp::<DefaultEnvironment>()
+}
+
+#[ink::trait_definition]
+pub trait Pausable {
+    #[ink(message)]
+    fn is_paused(&self, function: FunctionId) -> bool;
+
+    /// Halts `function` until the current pause window closes. Requires the
+    /// `PAUSER` role.
+    #[ink(message)]
+    fn pause(&mut self, function: FunctionId) -> Result<(), PauseError>;
+
+    /// Resumes `function`. Only reachable through governance.
+    #[ink(message)]
+    fn unpause(&mut self, function: FunctionId) -> Result<(), PauseError>;
+}
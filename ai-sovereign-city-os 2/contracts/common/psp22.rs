@@ -0,0 +1,76 @@
+//! The PSP22 fungible token standard, as called by and implemented in the
+//! city contracts.
+
+use crate::{AccountId, Balance, BlockNumber};
+use ink::prelude::string::String;
+use ink::prelude::vec::Vec;
+
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    Custom(String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(String),
+}
+
+#[ink::trait_definition]
+pub trait PSP22 {
+    #[ink(message)]
+    fn total_supply(&self) -> Balance;
+
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error>;
+}
+
+/// Balance history of a PSP22 token, so votes can be weighed by what
+/// accounts held before a proposal was opened rather than by what they hold
+/// when voting.
+#[ink::trait_definition]
+pub trait PSP22Votes {
+    /// Balance of `owner` as it stood before `block`.
+    #[ink(message)]
+    fn balance_of_at(&self, owner: AccountId, block: BlockNumber) -> Balance;
+
+    /// Total supply as it stood before `block`.
+    #[ink(message)]
+    fn total_supply_at(&self, block: BlockNumber) -> Balance;
+}
+
+#[ink::trait_definition]
+pub trait PSP22Metadata {
+    #[ink(message)]
+    fn token_name(&self) -> Option<String>;
+
+    #[ink(message)]
+    fn token_symbol(&self) -> Option<String>;
+
+    #[ink(message)]
+    fn token_decimals(&self) -> u8;
+}
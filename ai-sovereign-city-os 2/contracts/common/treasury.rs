@@ -0,0 +1,82 @@
+//! Interface of the city treasury, through which passed proposals are funded.
+
+use crate::{AccountId, Balance, ProposalId, Timestamp, ZoneId};
+use ink::prelude::vec::Vec;
+
+/// An asset the treasury holds: the chain's native currency or a PSP22 token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum Asset {
+    Native,
+    Token(AccountId),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TreasuryError {
+    Unauthorized,
+    InsufficientFunds,
+    ReservationExists,
+    ReservationNotFound,
+    NoTranchesLeft,
+    InvalidTranches,
+    TransferFailed,
+    StreamNotFound,
+    InvalidSchedule,
+    AlreadyDrawn,
+}
+
+#[ink::trait_definition]
+pub trait Treasury {
+    /// Balance of `asset` not reserved for any proposal.
+    #[ink(message)]
+    fn available(&self, asset: Asset) -> Balance;
+
+    /// Sets aside the sum of `tranches` for a passed proposal.
+    #[ink(message)]
+    fn reserve(
+        &mut self,
+        proposal_id: ProposalId,
+        asset: Asset,
+        beneficiary: AccountId,
+        tranches: Vec<Balance>,
+    ) -> Result<(), TreasuryError>;
+
+    /// Pays the next tranche of a reservation to its beneficiary.
+    #[ink(message)]
+    fn release_next_tranche(&mut self, proposal_id: ProposalId) -> Result<Balance, TreasuryError>;
+
+    /// Turns what is left of a reservation into a stream to its beneficiary
+    /// that vests linearly from `start` to `end`, with nothing claimable
+    /// before `cliff`.
+    #[ink(message)]
+    fn stream_reservation(
+        &mut self,
+        proposal_id: ProposalId,
+        start: Timestamp,
+        cliff: Timestamp,
+        end: Timestamp,
+    ) -> Result<Balance, TreasuryError>;
+
+    /// Returns whatever is left of a reservation to the available balance.
+    #[ink(message)]
+    fn cancel_reservation(&mut self, proposal_id: ProposalId) -> Result<Balance, TreasuryError>;
+}
+
+/// Interface the zone treasury draws each zone's period budget through.
+#[ink::trait_definition]
+pub trait ZoneFunding {
+    /// Pays `amount` of the native asset to `recipient` as the budget of
+    /// zone `zone_id` for `period`. Each zone and period draws once.
+    #[ink(message)]
+    fn draw_zone_budget(
+        &mut self,
+        zone_id: ZoneId,
+        period: u64,
+        recipient: AccountId,
+        amount: Balance,
+    ) -> Result<(), TreasuryError>;
+}
@@ -0,0 +1,18 @@
+//! Interface of the zone registry that scopes proposals to city zones.
+
+use crate::{AccountId, BlockNumber, ZoneId};
+
+#[ink::trait_definition]
+pub trait ZoneDirectory {
+    /// Zone `account` is a resident of, if any.
+    #[ink(message)]
+    fn zone_of(&self, account: AccountId) -> Option<ZoneId>;
+
+    /// Zone `account` was a resident of before `block`, if any.
+    #[ink(message)]
+    fn zone_of_at(&self, account: AccountId, block: BlockNumber) -> Option<ZoneId>;
+
+    /// Number of residents of `zone`; `None` if no such zone exists.
+    #[ink(message)]
+    fn population(&self, zone: ZoneId) -> Option<u32>;
+}
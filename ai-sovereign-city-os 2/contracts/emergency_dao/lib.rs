@@ -0,0 +1,600 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod emergency_dao {
+    use city_common::access::{AccessControl, AccessControlled, AccessError, RoleId, ORACLE_ADMIN, PAUSER};
+    use city_common::pausable::{FunctionId, PauseError, PauseState, Pausable};
+    use ink::storage::{Lazy, Mapping};
+    use ink::prelude::string::String;
+
+    /// Layout version of this contract's storage. Bump it together with a
+    /// step in `migrate` whenever stored types change. Fields added later
+    /// must live outside the root cell (in a `Lazy` or `Mapping`) so the
+    /// root still decodes after `set_code`.
+    pub const STORAGE_VERSION: u32 = 1;
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        ZeroContribution,
+        InvalidSeverity,
+        Unauthorized,
+        IncidentNotFound,
+        IncidentNotVerified,
+        AlreadyVoted,
+        InsufficientFunds,
+        UpgradeFailed,
+        UnsupportedStorageVersion,
+        Paused,
+        PauseCoolingDown,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl From<PauseError> for Error {
+        fn from(error: PauseError) -> Self {
+            match error {
+                PauseError::Paused => Error::Paused,
+                PauseError::Unauthorized => Error::Unauthorized,
+                PauseError::CoolingDown => Error::PauseCoolingDown,
+            }
+        }
+    }
+
+    impl From<AccessError> for Error {
+        fn from(_: AccessError) -> Self {
+            Error::Unauthorized
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum EmergencyType {
+        Fire,
+        Flood,
+        PowerOutage,
+        CyberAttack,
+        NaturalDisaster,
+        MedicalEmergency,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum IncidentStatus {
+        Reported,
+        Verified,
+        Responding,
+        Resolved,
+        PayoutApproved,
+        PayoutExecuted,
+    }
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct EmergencyIncident {
+        pub id: u32,
+        pub incident_type: EmergencyType,
+        pub location: String,
+        pub description: String,
+        pub reporter: AccountId,
+        pub status: IncidentStatus,
+        pub severity: u32, // 1-10 scale
+        pub estimated_cost: u128,
+        pub actual_cost: u128,
+        pub ai_confidence: u32,
+        pub timestamp: u64,
+        pub response_time: u64,
+        pub affected_citizens: u32,
+    }
+
+    #[ink(storage)]
+    pub struct EmergencyDAO {
+        incidents: Mapping<u32, EmergencyIncident>,
+        fund_balance: u128,
+        contributions: Mapping<AccountId, u128>,
+        incident_count: u32,
+        total_payouts: u128,
+        access: AccessControl,
+        pauses: PauseState,
+        governance: AccountId,
+        ai_oracle: AccountId,
+        min_payout_votes: u32,
+        payout_votes: Mapping<(u32, AccountId), bool>, // (incident_id, voter) -> approve
+        payout_approvals: Mapping<u32, u32>,
+        storage_version: Lazy<u32>,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        function: FunctionId,
+        account: AccountId,
+        until: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        function: FunctionId,
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        code_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct StorageMigrated {
+        from: u32,
+        to: u32,
+    }
+
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        pending_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct EmergencyReported {
+        #[ink(topic)]
+        incident_id: u32,
+        #[ink(topic)]
+        reporter: AccountId,
+        incident_type: EmergencyType,
+        severity: u32,
+    }
+
+    #[ink(event)]
+    pub struct FundContribution {
+        #[ink(topic)]
+        contributor: AccountId,
+        amount: u128,
+        new_balance: u128,
+    }
+
+    #[ink(event)]
+    pub struct EmergencyPayout {
+        #[ink(topic)]
+        incident_id: u32,
+        amount: u128,
+        recipient: AccountId,
+    }
+
+    impl EmergencyDAO {
+        #[ink(constructor)]
+        pub fn new(governance: AccountId, ai_oracle: AccountId, min_payout_votes: u32) -> Self {
+            let caller = Self::env().caller();
+            let mut access = AccessControl::new(caller);
+            access.set_role(PAUSER, caller);
+            let mut storage_version = Lazy::new();
+            storage_version.set(&STORAGE_VERSION);
+            Self {
+                incidents: Mapping::default(),
+                fund_balance: 0,
+                contributions: Mapping::default(),
+                incident_count: 0,
+                total_payouts: 0,
+                access,
+                pauses: PauseState::default(),
+                governance,
+                ai_oracle,
+                min_payout_votes,
+                payout_votes: Mapping::default(),
+                payout_approvals: Mapping::default(),
+                storage_version,
+            }
+        }
+
+        #[ink(message, payable)]
+        pub fn contribute_to_fund(&mut self) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("contribute_to_fund"))?;
+            let caller = self.env().caller();
+            let amount = self.env().transferred_value();
+            
+            if amount == 0 {
+                return Err(Error::ZeroContribution);
+            }
+
+            self.fund_balance += amount;
+            let current_contribution = self.contributions.get(caller).unwrap_or(0);
+            self.contributions.insert(caller, &(current_contribution + amount));
+
+            self.env().emit_event(FundContribution {
+                contributor: caller,
+                amount,
+                new_balance: self.fund_balance,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn report_emergency(
+            &mut self,
+            incident_type: EmergencyType,
+            location: String,
+            description: String,
+            severity: u32,
+            affected_citizens: u32,
+        ) -> Result<u32> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("report_emergency"))?;
+            let caller = self.env().caller();
+            let incident_id = self.incident_count;
+
+            if severity > 10 {
+                return Err(Error::InvalidSeverity);
+            }
+
+            let incident = EmergencyIncident {
+                id: incident_id,
+                incident_type: incident_type.clone(),
+                location,
+                description,
+                reporter: caller,
+                status: IncidentStatus::Reported,
+                severity,
+                estimated_cost: 0,
+                actual_cost: 0,
+                ai_confidence: 0,
+                timestamp: self.env().block_timestamp(),
+                response_time: 0,
+                affected_citizens,
+            };
+
+            self.incidents.insert(incident_id, &incident);
+            self.incident_count += 1;
+
+            self.env().emit_event(EmergencyReported {
+                incident_id,
+                reporter: caller,
+                incident_type,
+                severity,
+            });
+
+            Ok(incident_id)
+        }
+
+        #[ink(message)]
+        pub fn ai_verify_incident(
+            &mut self,
+            incident_id: u32,
+            confidence: u32,
+            estimated_cost: u128,
+        ) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("ai_verify_incident"))?;
+            if self.env().caller() != self.ai_oracle {
+                return Err(Error::Unauthorized);
+            }
+
+            let mut incident = self.incidents.get(incident_id).ok_or(Error::IncidentNotFound)?;
+            
+            incident.ai_confidence = confidence;
+            incident.estimated_cost = estimated_cost;
+            
+            if confidence >= 80 {
+                incident.status = IncidentStatus::Verified;
+            }
+
+            self.incidents.insert(incident_id, &incident);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_ai_oracle(&self) -> AccountId {
+            self.ai_oracle
+        }
+
+        /// Replaces the account allowed to verify incidents. The governance
+        /// contract can always do so, and so can holders of `ORACLE_ADMIN`,
+        /// which the deployer is not given.
+        #[ink(message)]
+        pub fn set_ai_oracle(&mut self, ai_oracle: AccountId) -> Result<()> {
+            if !self.access.has_role(ORACLE_ADMIN, self.env().caller()) {
+                self.ensure_governance()?;
+            }
+            self.ai_oracle = ai_oracle;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn vote_for_payout(&mut self, incident_id: u32, approve: bool) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("vote_for_payout"))?;
+            let caller = self.env().caller();
+            let incident = self.incidents.get(incident_id).ok_or(Error::IncidentNotFound)?;
+
+            if incident.status != IncidentStatus::Verified {
+                return Err(Error::IncidentNotVerified);
+            }
+
+            // Check if already voted
+            if self.payout_votes.contains((incident_id, caller)) {
+                return Err(Error::AlreadyVoted);
+            }
+
+            // Record vote
+            self.payout_votes.insert((incident_id, caller), &approve);
+
+            // Check if enough votes for payout
+            let mut approve_count = self.payout_approvals.get(incident_id).unwrap_or(0);
+            if approve {
+                approve_count += 1;
+                self.payout_approvals.insert(incident_id, &approve_count);
+            }
+
+            if approve_count >= self.min_payout_votes {
+                self.execute_payout(incident_id)?;
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn execute_payout(&mut self, incident_id: u32) -> Result<()> {
+            self.pauses.ensure_not_paused(ink::selector_bytes!("execute_payout"))?;
+            let mut incident = self.incidents.get(incident_id).ok_or(Error::IncidentNotFound)?;
+            
+            if incident.status != IncidentStatus::Verified {
+                return Err(Error::IncidentNotVerified);
+            }
+
+            let payout_amount = incident.estimated_cost;
+            
+            if payout_amount > self.fund_balance {
+                return Err(Error::InsufficientFunds);
+            }
+
+            // Execute payout
+            self.fund_balance -= payout_amount;
+            self.total_payouts += payout_amount;
+            incident.status = IncidentStatus::PayoutExecuted;
+            incident.actual_cost = payout_amount;
+
+            self.incidents.insert(incident_id, &incident);
+
+            // In real implementation, transfer funds to affected parties
+            self.env().emit_event(EmergencyPayout {
+                incident_id,
+                amount: payout_amount,
+                recipient: incident.reporter,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_incident(&self, incident_id: u32) -> Option<EmergencyIncident> {
+            self.incidents.get(incident_id)
+        }
+
+        #[ink(message)]
+        pub fn get_fund_stats(&self) -> (u128, u128, u32) {
+            (self.fund_balance, self.total_payouts, self.incident_count)
+        }
+
+        #[ink(message)]
+        pub fn get_contribution(&self, contributor: AccountId) -> u128 {
+            self.contributions.get(contributor).unwrap_or(0)
+        }
+
+        /// Replaces this contract's code, keeping its storage. Only the governance
+        /// contract may call it, through an executed proposal.
+        /// Follow it with `migrate` in the same proposal.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<()> {
+            self.ensure_governance()?;
+            self.env().set_code_hash(&code_hash).map_err(|_| Error::UpgradeFailed)?;
+            self.env().emit_event(CodeUpgraded { code_hash });
+            Ok(())
+        }
+
+        /// Brings storage written by an older version up to
+        /// `STORAGE_VERSION`. Only reachable through an executed proposal.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<()> {
+            self.ensure_governance()?;
+            let from = self.storage_version.get().unwrap_or(STORAGE_VERSION);
+            if from > STORAGE_VERSION {
+                return Err(Error::UnsupportedStorageVersion);
+            }
+            // Steps for later versions go here, oldest first, e.g. rewriting
+            // `Mapping` entries whose type changed:
+            // if from < 2 { ... }
+            self.storage_version.set(&STORAGE_VERSION);
+            self.env().emit_event(StorageMigrated { from, to: STORAGE_VERSION });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or(STORAGE_VERSION)
+        }
+
+        fn ensure_governance(&self) -> Result<()> {
+            if self.env().caller() != self.governance {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+    }
+
+    impl AccessControlled for EmergencyDAO {
+        #[ink(message)]
+        fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+            self.access.has_role(role, account)
+        }
+
+        #[ink(message)]
+        fn grant_role(&mut self, role: RoleId, account: AccountId) -> core::result::Result<(), AccessError> {
+            let sender = self.env().caller();
+            if self.access.grant_role(sender, role, account)? {
+                self.env().emit_event(RoleGranted { role, account, sender });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn revoke_role(&mut self, role: RoleId, account: AccountId) -> core::result::Result<(), AccessError> {
+            let sender = self.env().caller();
+            if self.access.revoke_role(sender, role, account)? {
+                self.env().emit_event(RoleRevoked { role, account, sender });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn renounce_role(&mut self, role: RoleId) -> core::result::Result<(), AccessError> {
+            let account = self.env().caller();
+            self.access.renounce_role(account, role)?;
+            self.env().emit_event(RoleRevoked { role, account, sender: account });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn owner(&self) -> AccountId {
+            self.access.owner()
+        }
+
+        #[ink(message)]
+        fn pending_owner(&self) -> Option<AccountId> {
+            self.access.pending_owner()
+        }
+
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> core::result::Result<(), AccessError> {
+            let owner = self.env().caller();
+            self.access.transfer_ownership(owner, new_owner)?;
+            self.env().emit_event(OwnershipTransferStarted {
+                owner,
+                pending_owner: new_owner,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn accept_ownership(&mut self) -> core::result::Result<(), AccessError> {
+            let new_owner = self.env().caller();
+            let previous_owner = self.access.accept_ownership(new_owner)?;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            });
+            Ok(())
+        }
+    }
+
+    impl Pausable for EmergencyDAO {
+        #[ink(message)]
+        fn is_paused(&self, function: FunctionId) -> bool {
+            self.pauses.is_paused(function)
+        }
+
+        #[ink(message)]
+        fn pause(&mut self, function: FunctionId) -> core::result::Result<(), PauseError> {
+            let account = self.env().caller();
+            if !self.access.has_role(PAUSER, account) {
+                return Err(PauseError::Unauthorized);
+            }
+            if self.pauses.pause(function)? {
+                let until = self.pauses.paused_until(function).unwrap_or_default();
+                self.env().emit_event(Paused { function, account, until });
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn unpause(&mut self, function: FunctionId) -> core::result::Result<(), PauseError> {
+            self.ensure_governance().map_err(|_| PauseError::Unauthorized)?;
+            if self.pauses.unpause(function) {
+                self.env().emit_event(Unpaused {
+                    function,
+                    account: self.env().caller(),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        /// DAO deployed by Alice, governed by Charlie, with Bob as the AI
+        /// oracle.
+        fn dao() -> EmergencyDAO {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            EmergencyDAO::new(accounts.charlie, accounts.bob, 1)
+        }
+
+        #[ink::test]
+        fn governance_and_oracle_admins_replace_the_oracle() {
+            let accounts = accounts();
+            let mut dao = dao();
+
+            // The deployer holds no oracle role of its own
+            assert!(!dao.has_role(ORACLE_ADMIN, accounts.alice));
+            assert_eq!(dao.set_ai_oracle(accounts.alice), Err(Error::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(dao.set_ai_oracle(accounts.bob), Err(Error::Unauthorized));
+
+            set_caller(accounts.charlie);
+            assert_eq!(dao.set_ai_oracle(accounts.django), Ok(()));
+            assert_eq!(dao.get_ai_oracle(), accounts.django);
+
+            set_caller(accounts.alice);
+            assert_eq!(dao.grant_role(ORACLE_ADMIN, accounts.eve), Ok(()));
+            set_caller(accounts.eve);
+            assert_eq!(dao.set_ai_oracle(accounts.frank), Ok(()));
+            assert_eq!(dao.get_ai_oracle(), accounts.frank);
+        }
+    }
+}
@@ -0,0 +1,598 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod oracle_set {
+    use city_common::call::{invoke, CallFailure};
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    /// Upper bound on the number of registered operators.
+    pub const MAX_OPERATORS: u32 = 32;
+    /// Slashing rates are expressed in basis points of the bonded stake.
+    pub const BASIS_POINTS: u128 = 10_000;
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        Unauthorized,
+        NotOperator,
+        AlreadyOperator,
+        TooManyOperators,
+        InsufficientStake,
+        ZeroAmount,
+        StillUnbonding,
+        NothingToWithdraw,
+        AlreadyAttested,
+        RoundClosed,
+        InvalidParams,
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Operator {
+        pub stake: Balance,
+        /// Stake on its way out; still slashable until withdrawn.
+        pub unbonding: Balance,
+        pub unlock_at: u64,
+        pub attestations: u32,
+        pub times_slashed: u32,
+    }
+
+    /// What an operator attests to: the call the set makes on a consumer
+    /// contract once enough operators agree on it.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Attestation {
+        pub callee: AccountId,
+        pub selector: [u8; 4],
+        /// SCALE-encoded message arguments, e.g. `(incident_id, confidence, estimated_cost)`.
+        pub input: Vec<u8>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum RoundStatus {
+        Open,
+        /// The agreed attestation was delivered to its consumer.
+        Accepted,
+        /// Operators agreed, but the consumer rejected the call.
+        DeliveryFailed(CallFailure),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Round {
+        pub status: RoundStatus,
+        /// Hash of the attestation that reached quorum.
+        pub accepted: Option<Hash>,
+        /// Operators that attested, in submission order.
+        pub attesters: Vec<AccountId>,
+    }
+
+    #[ink(storage)]
+    pub struct OracleSet {
+        operators: Vec<AccountId>,
+        operator_info: Mapping<AccountId, Operator>,
+        rounds: Mapping<Hash, Round>,
+        submissions: Mapping<(Hash, AccountId), Hash>, // (round_id, operator) -> attestation hash
+        tallies: Mapping<(Hash, Hash), u32>,           // (round_id, attestation hash) -> operators agreeing
+        quorum: u32,
+        min_stake: Balance,
+        slash_bps: u128,
+        unbonding_period: u64,
+        slashed_funds: Balance,
+        governance: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OperatorAdded {
+        #[ink(topic)]
+        operator: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OperatorRemoved {
+        #[ink(topic)]
+        operator: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct StakeBonded {
+        #[ink(topic)]
+        operator: AccountId,
+        amount: Balance,
+        stake: Balance,
+    }
+
+    #[ink(event)]
+    pub struct StakeUnbonded {
+        #[ink(topic)]
+        operator: AccountId,
+        amount: Balance,
+        unlock_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct AttestationSubmitted {
+        #[ink(topic)]
+        round_id: Hash,
+        #[ink(topic)]
+        operator: AccountId,
+        attestation_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct RoundFinalized {
+        #[ink(topic)]
+        round_id: Hash,
+        attestation_hash: Hash,
+        status: RoundStatus,
+    }
+
+    #[ink(event)]
+    pub struct OperatorSlashed {
+        #[ink(topic)]
+        round_id: Hash,
+        #[ink(topic)]
+        operator: AccountId,
+        amount: Balance,
+    }
+
+    impl OracleSet {
+        #[ink(constructor)]
+        pub fn new(
+            governance: AccountId,
+            quorum: u32,
+            min_stake: Balance,
+            slash_bps: u128,
+            unbonding_period: u64,
+        ) -> Self {
+            assert!(Self::is_valid_params(quorum, slash_bps), "invalid oracle set parameters");
+            Self {
+                operators: Vec::new(),
+                operator_info: Mapping::default(),
+                rounds: Mapping::default(),
+                submissions: Mapping::default(),
+                tallies: Mapping::default(),
+                quorum,
+                min_stake,
+                slash_bps,
+                unbonding_period,
+                slashed_funds: 0,
+                governance,
+            }
+        }
+
+        #[ink(message)]
+        pub fn add_operator(&mut self, operator: AccountId) -> Result<()> {
+            self.ensure_governance()?;
+            if self.operators.contains(&operator) {
+                return Err(Error::AlreadyOperator);
+            }
+            if self.operators.len() >= MAX_OPERATORS as usize {
+                return Err(Error::TooManyOperators);
+            }
+
+            self.operators.push(operator);
+            if !self.operator_info.contains(operator) {
+                self.operator_info.insert(operator, &Operator::default());
+            }
+
+            self.env().emit_event(OperatorAdded { operator });
+            Ok(())
+        }
+
+        /// Removes an operator from the set. Their stake stays bonded and can
+        /// be unbonded as usual.
+        #[ink(message)]
+        pub fn remove_operator(&mut self, operator: AccountId) -> Result<()> {
+            self.ensure_governance()?;
+            let position = self.operators.iter().position(|account| *account == operator).ok_or(Error::NotOperator)?;
+            self.operators.swap_remove(position);
+
+            self.env().emit_event(OperatorRemoved { operator });
+            Ok(())
+        }
+
+        #[ink(message, payable)]
+        pub fn bond(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            let mut info = self.operator_info.get(caller).unwrap_or_default();
+            info.stake += amount;
+            self.operator_info.insert(caller, &info);
+
+            self.env().emit_event(StakeBonded {
+                operator: caller,
+                amount,
+                stake: info.stake,
+            });
+
+            Ok(())
+        }
+
+        /// Starts unbonding `amount`. It stays slashable until it can be
+        /// withdrawn after the unbonding period; unbonding again restarts it.
+        #[ink(message)]
+        pub fn unbond(&mut self, amount: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            let mut info = self.operator_info.get(caller).ok_or(Error::NotOperator)?;
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            if amount > info.stake {
+                return Err(Error::InsufficientStake);
+            }
+
+            info.stake -= amount;
+            info.unbonding += amount;
+            info.unlock_at = self.env().block_timestamp() + self.unbonding_period;
+            self.operator_info.insert(caller, &info);
+
+            self.env().emit_event(StakeUnbonded {
+                operator: caller,
+                amount,
+                unlock_at: info.unlock_at,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn withdraw_unbonded(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            let mut info = self.operator_info.get(caller).ok_or(Error::NotOperator)?;
+            if info.unbonding == 0 {
+                return Err(Error::NothingToWithdraw);
+            }
+            if self.env().block_timestamp() < info.unlock_at {
+                return Err(Error::StillUnbonding);
+            }
+
+            let amount = info.unbonding;
+            info.unbonding = 0;
+            self.operator_info.insert(caller, &info);
+            self.env().transfer(caller, amount).map_err(|_| Error::TransferFailed)?;
+
+            Ok(())
+        }
+
+        /// Records the caller's attestation for `round_id`. When `quorum`
+        /// operators have submitted the same attestation, it is delivered to
+        /// its consumer and every operator that attested otherwise is slashed.
+        #[ink(message)]
+        pub fn submit_attestation(&mut self, round_id: Hash, attestation: Attestation) -> Result<()> {
+            let caller = self.env().caller();
+            if !self.operators.contains(&caller) {
+                return Err(Error::NotOperator);
+            }
+            let mut info = self.operator_info.get(caller).unwrap_or_default();
+            if info.stake < self.min_stake {
+                return Err(Error::InsufficientStake);
+            }
+
+            let mut round = self.rounds.get(round_id).unwrap_or(Round {
+                status: RoundStatus::Open,
+                accepted: None,
+                attesters: Vec::new(),
+            });
+            if round.status != RoundStatus::Open {
+                return Err(Error::RoundClosed);
+            }
+            if self.submissions.contains((round_id, caller)) {
+                return Err(Error::AlreadyAttested);
+            }
+
+            let attestation_hash = Self::hash_attestation(&attestation);
+            self.submissions.insert((round_id, caller), &attestation_hash);
+            let agreeing = self.tallies.get((round_id, attestation_hash)).unwrap_or(0) + 1;
+            self.tallies.insert((round_id, attestation_hash), &agreeing);
+            round.attesters.push(caller);
+            info.attestations += 1;
+            self.operator_info.insert(caller, &info);
+
+            self.env().emit_event(AttestationSubmitted {
+                round_id,
+                operator: caller,
+                attestation_hash,
+            });
+
+            if agreeing >= self.quorum {
+                // Close the round before calling out
+                round.accepted = Some(attestation_hash);
+                round.status = RoundStatus::Accepted;
+                self.rounds.insert(round_id, &round);
+
+                if let Err(reason) = invoke(attestation.callee, attestation.selector, &attestation.input, 0, false) {
+                    round.status = RoundStatus::DeliveryFailed(reason);
+                }
+                self.slash_dissenters(round_id, &round, attestation_hash);
+
+                self.env().emit_event(RoundFinalized {
+                    round_id,
+                    attestation_hash,
+                    status: round.status.clone(),
+                });
+            }
+            self.rounds.insert(round_id, &round);
+
+            Ok(())
+        }
+
+        /// Sends slashed stake to `to`, typically the city treasury.
+        #[ink(message)]
+        pub fn withdraw_slashed(&mut self, to: AccountId, amount: Balance) -> Result<()> {
+            self.ensure_governance()?;
+            if amount > self.slashed_funds {
+                return Err(Error::InsufficientStake);
+            }
+
+            self.slashed_funds -= amount;
+            self.env().transfer(to, amount).map_err(|_| Error::TransferFailed)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_params(&mut self, quorum: u32, min_stake: Balance, slash_bps: u128, unbonding_period: u64) -> Result<()> {
+            self.ensure_governance()?;
+            if !Self::is_valid_params(quorum, slash_bps) {
+                return Err(Error::InvalidParams);
+            }
+
+            self.quorum = quorum;
+            self.min_stake = min_stake;
+            self.slash_bps = slash_bps;
+            self.unbonding_period = unbonding_period;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_operators(&self) -> Vec<AccountId> {
+            self.operators.clone()
+        }
+
+        #[ink(message)]
+        pub fn get_operator(&self, operator: AccountId) -> Option<Operator> {
+            self.operator_info.get(operator)
+        }
+
+        #[ink(message)]
+        pub fn get_round(&self, round_id: Hash) -> Option<Round> {
+            self.rounds.get(round_id)
+        }
+
+        #[ink(message)]
+        pub fn get_submission(&self, round_id: Hash, operator: AccountId) -> Option<Hash> {
+            self.submissions.get((round_id, operator))
+        }
+
+        /// Returns `(quorum, min_stake, slash_bps, unbonding_period, slashed_funds)`.
+        #[ink(message)]
+        pub fn get_params(&self) -> (u32, Balance, u128, u64, Balance) {
+            (self.quorum, self.min_stake, self.slash_bps, self.unbonding_period, self.slashed_funds)
+        }
+
+        /// Hash operators' submissions are compared by.
+        #[ink(message)]
+        pub fn get_attestation_hash(&self, attestation: Attestation) -> Hash {
+            Self::hash_attestation(&attestation)
+        }
+
+        fn hash_attestation(attestation: &Attestation) -> Hash {
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(attestation, &mut output);
+            output.into()
+        }
+
+        /// Slashes every attester of `round` whose submission differs from
+        /// the accepted one, taking from bonded stake before unbonding stake.
+        fn slash_dissenters(&mut self, round_id: Hash, round: &Round, accepted: Hash) {
+            for operator in round.attesters.iter().copied() {
+                if self.submissions.get((round_id, operator)) == Some(accepted) {
+                    continue;
+                }
+                let Some(mut info) = self.operator_info.get(operator) else {
+                    continue;
+                };
+
+                let amount = (info.stake + info.unbonding) * self.slash_bps / BASIS_POINTS;
+                let from_stake = amount.min(info.stake);
+                info.stake -= from_stake;
+                info.unbonding -= amount - from_stake;
+                info.times_slashed += 1;
+                self.operator_info.insert(operator, &info);
+                self.slashed_funds += amount;
+
+                self.env().emit_event(OperatorSlashed {
+                    round_id,
+                    operator,
+                    amount,
+                });
+            }
+        }
+
+        fn ensure_governance(&self) -> Result<()> {
+            if self.env().caller() != self.governance {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+
+        /// A single operator must never be able to decide a round alone.
+        fn is_valid_params(quorum: u32, slash_bps: u128) -> bool {
+            quorum >= 2 && slash_bps <= BASIS_POINTS
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        const MIN_STAKE: Balance = 100;
+        const SLASH_BPS: u128 = 1_000;
+        const UNBONDING_PERIOD: u64 = 50;
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        fn bond(oracle_set: &mut OracleSet, operator: AccountId, amount: Balance) {
+            set_caller(operator);
+            test::set_value_transferred::<DefaultEnvironment>(amount);
+            assert_eq!(oracle_set.bond(), Ok(()));
+            test::set_value_transferred::<DefaultEnvironment>(0);
+        }
+
+        /// Set governed by Alice with a quorum of three, where Bob, Charlie
+        /// and Django have bonded 1000 each.
+        fn oracle_set() -> OracleSet {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut oracle_set = OracleSet::new(accounts.alice, 3, MIN_STAKE, SLASH_BPS, UNBONDING_PERIOD);
+            for operator in [accounts.bob, accounts.charlie, accounts.django] {
+                set_caller(accounts.alice);
+                assert_eq!(oracle_set.add_operator(operator), Ok(()));
+                bond(&mut oracle_set, operator, 1_000);
+            }
+            oracle_set
+        }
+
+        fn attestation(confidence: u8) -> Attestation {
+            Attestation {
+                callee: AccountId::from([0xEE; 32]),
+                selector: ink::selector_bytes!("ai_verify_incident"),
+                input: scale::Encode::encode(&(0u32, confidence)),
+            }
+        }
+
+        #[ink::test]
+        fn one_operator_cannot_decide_a_round() {
+            let accounts = accounts();
+            let mut oracle_set = oracle_set();
+            set_caller(accounts.alice);
+            assert_eq!(oracle_set.set_params(1, MIN_STAKE, SLASH_BPS, UNBONDING_PERIOD), Err(Error::InvalidParams));
+
+            let round_id = Hash::from([1; 32]);
+            set_caller(accounts.eve);
+            assert_eq!(oracle_set.submit_attestation(round_id, attestation(90)), Err(Error::NotOperator));
+            set_caller(accounts.bob);
+            assert_eq!(oracle_set.submit_attestation(round_id, attestation(90)), Ok(()));
+            assert_eq!(oracle_set.submit_attestation(round_id, attestation(90)), Err(Error::AlreadyAttested));
+
+            let round = oracle_set.get_round(round_id).unwrap();
+            assert_eq!(round.status, RoundStatus::Open);
+            assert_eq!(round.attesters, vec![accounts.bob]);
+        }
+
+        #[ink::test]
+        fn operators_below_the_minimum_stake_cannot_attest() {
+            let accounts = accounts();
+            let mut oracle_set = oracle_set();
+            set_caller(accounts.bob);
+            assert_eq!(oracle_set.unbond(950), Ok(()));
+            assert_eq!(
+                oracle_set.submit_attestation(Hash::from([1; 32]), attestation(90)),
+                Err(Error::InsufficientStake)
+            );
+        }
+
+        #[ink::test]
+        fn dissenters_are_slashed_from_stake_then_unbonding() {
+            let accounts = accounts();
+            let mut oracle_set = oracle_set();
+            let round_id = Hash::from([1; 32]);
+
+            // Django moves most of his stake out, which stays slashable
+            set_caller(accounts.django);
+            assert_eq!(oracle_set.unbond(950), Ok(()));
+            set_caller(accounts.alice);
+            assert_eq!(oracle_set.set_params(3, 0, SLASH_BPS, UNBONDING_PERIOD), Ok(()));
+
+            for (operator, confidence) in [(accounts.bob, 90), (accounts.charlie, 90), (accounts.django, 10)] {
+                set_caller(operator);
+                assert_eq!(oracle_set.submit_attestation(round_id, attestation(confidence)), Ok(()));
+            }
+            let round = oracle_set.get_round(round_id).unwrap();
+            let accepted = oracle_set.get_attestation_hash(attestation(90));
+            oracle_set.slash_dissenters(round_id, &round, accepted);
+
+            let slashed = oracle_set.get_operator(accounts.django).unwrap();
+            assert_eq!((slashed.stake, slashed.unbonding, slashed.times_slashed), (0, 900, 1));
+            assert_eq!(oracle_set.get_operator(accounts.bob).unwrap().stake, 1_000);
+            assert_eq!(oracle_set.get_params().4, 100);
+        }
+
+        #[ink::test]
+        fn unbonded_stake_is_withdrawn_after_the_period() {
+            let accounts = accounts();
+            let mut oracle_set = oracle_set();
+            test::set_account_balance::<DefaultEnvironment>(test::callee::<DefaultEnvironment>(), 3_000);
+            let balance_before = test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(oracle_set.withdraw_unbonded(), Err(Error::NothingToWithdraw));
+            assert_eq!(oracle_set.unbond(400), Ok(()));
+            assert_eq!(oracle_set.withdraw_unbonded(), Err(Error::StillUnbonding));
+
+            test::set_block_timestamp::<DefaultEnvironment>(UNBONDING_PERIOD);
+            assert_eq!(oracle_set.withdraw_unbonded(), Ok(()));
+            assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap(), balance_before + 400);
+            assert_eq!(oracle_set.get_operator(accounts.bob).unwrap().stake, 600);
+        }
+
+        #[ink::test]
+        fn rounds_reaching_quorum_are_closed_and_slash_dissenters() {
+            let accounts = accounts();
+            let mut oracle_set = oracle_set();
+            set_caller(accounts.alice);
+            assert_eq!(oracle_set.set_params(2, MIN_STAKE, SLASH_BPS, UNBONDING_PERIOD), Ok(()));
+            let round_id = Hash::from([1; 32]);
+
+            for (operator, confidence) in [(accounts.bob, 90), (accounts.django, 10), (accounts.charlie, 90)] {
+                set_caller(operator);
+                assert_eq!(oracle_set.submit_attestation(round_id, attestation(confidence)), Ok(()));
+            }
+
+            // The consumer is a plain account off-chain, so delivery reports it
+            let round = oracle_set.get_round(round_id).unwrap();
+            assert_eq!(round.status, RoundStatus::DeliveryFailed(CallFailure::NotCallable));
+            assert_eq!(round.accepted, Some(oracle_set.get_attestation_hash(attestation(90))));
+
+            let slashed = oracle_set.get_operator(accounts.django).unwrap();
+            assert_eq!((slashed.stake, slashed.times_slashed), (900, 1));
+            assert_eq!(oracle_set.get_operator(accounts.bob).unwrap().times_slashed, 0);
+            assert_eq!(oracle_set.get_params().4, 100);
+
+            set_caller(accounts.alice);
+            assert_eq!(oracle_set.add_operator(accounts.eve), Ok(()));
+            bond(&mut oracle_set, accounts.eve, 1_000);
+            assert_eq!(oracle_set.submit_attestation(round_id, attestation(90)), Err(Error::RoundClosed));
+        }
+    }
+}
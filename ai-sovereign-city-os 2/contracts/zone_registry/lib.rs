@@ -0,0 +1,418 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod zone_registry {
+    use city_common::citizenship::CitizenRegistry;
+    use city_common::zones::ZoneDirectory;
+    use city_common::ZoneId;
+    use ink::contract_ref;
+    use ink::prelude::string::String;
+    use ink::storage::Mapping;
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        Unauthorized,
+        ZoneNotFound,
+        NotCitizen,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Zone {
+        pub name: String,
+        /// Office that records who lives in the zone.
+        pub registrar: AccountId,
+        pub population: u32,
+    }
+
+    /// Zone an account lived in from `block` onwards.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ResidencyCheckpoint {
+        pub block: BlockNumber,
+        pub zone: Option<ZoneId>,
+    }
+
+    #[ink(storage)]
+    pub struct ZoneRegistry {
+        zones: Mapping<ZoneId, Zone>,
+        residency: Mapping<AccountId, ZoneId>,
+        checkpoints: Mapping<(AccountId, u32), ResidencyCheckpoint>,
+        checkpoint_counts: Mapping<AccountId, u32>,
+        zone_count: u32,
+        governance: AccountId,
+        /// Registry whose credentials are required to become a resident;
+        /// `None` lets any account move in.
+        citizen_registry: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct ZoneCreated {
+        #[ink(topic)]
+        zone_id: ZoneId,
+        name: String,
+        registrar: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RegistrarUpdated {
+        #[ink(topic)]
+        zone_id: ZoneId,
+        registrar: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct CitizenRegistryUpdated {
+        citizen_registry: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct ResidencyChanged {
+        #[ink(topic)]
+        citizen: AccountId,
+        from: Option<ZoneId>,
+        to: Option<ZoneId>,
+    }
+
+    impl ZoneRegistry {
+        #[ink(constructor)]
+        pub fn new(governance: AccountId, citizen_registry: Option<AccountId>) -> Self {
+            Self {
+                zones: Mapping::default(),
+                residency: Mapping::default(),
+                checkpoints: Mapping::default(),
+                checkpoint_counts: Mapping::default(),
+                zone_count: 0,
+                governance,
+                citizen_registry,
+            }
+        }
+
+        #[ink(message)]
+        pub fn create_zone(&mut self, name: String, registrar: AccountId) -> Result<ZoneId> {
+            self.ensure_governance()?;
+
+            let zone_id = self.zone_count;
+            self.zones.insert(
+                zone_id,
+                &Zone {
+                    name: name.clone(),
+                    registrar,
+                    population: 0,
+                },
+            );
+            self.zone_count += 1;
+
+            self.env().emit_event(ZoneCreated {
+                zone_id,
+                name,
+                registrar,
+            });
+
+            Ok(zone_id)
+        }
+
+        #[ink(message)]
+        pub fn set_registrar(&mut self, zone_id: ZoneId, registrar: AccountId) -> Result<()> {
+            self.ensure_governance()?;
+            let mut zone = self.zones.get(zone_id).ok_or(Error::ZoneNotFound)?;
+            zone.registrar = registrar;
+            self.zones.insert(zone_id, &zone);
+
+            self.env().emit_event(RegistrarUpdated { zone_id, registrar });
+            Ok(())
+        }
+
+        /// Moves `citizen` into `zone`, or out of their zone with `None`.
+        /// Leaving a zone takes its registrar and entering one takes the
+        /// registrar of the zone entered, so a move between zones is signed
+        /// off by both: the old registrar moves the citizen out, then the new
+        /// one moves them in. Governance may do either, or both at once. Only
+        /// citizens can move in.
+        #[ink(message)]
+        pub fn set_residency(&mut self, citizen: AccountId, zone: Option<ZoneId>) -> Result<()> {
+            let caller = self.env().caller();
+            let from = self.residency.get(citizen);
+            if from == zone {
+                return Ok(());
+            }
+            if let Some(from) = from {
+                self.ensure_registrar(from, caller)?;
+            }
+            if let Some(to) = zone {
+                self.ensure_registrar(to, caller)?;
+                if !self.is_citizen(citizen) {
+                    return Err(Error::NotCitizen);
+                }
+            }
+
+            if let Some(from) = from {
+                self.update_population(from, |population| population.saturating_sub(1));
+            }
+            match zone {
+                Some(to) => {
+                    self.update_population(to, |population| population + 1);
+                    self.residency.insert(citizen, &to);
+                }
+                None => self.residency.remove(citizen),
+            }
+            self.write_checkpoint(citizen, zone);
+
+            self.env().emit_event(ResidencyChanged {
+                citizen,
+                from,
+                to: zone,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_zone(&self, zone_id: ZoneId) -> Option<Zone> {
+            self.zones.get(zone_id)
+        }
+
+        #[ink(message)]
+        pub fn get_checkpoint(&self, citizen: AccountId, index: u32) -> Option<ResidencyCheckpoint> {
+            self.checkpoints.get((citizen, index))
+        }
+
+        #[ink(message)]
+        pub fn get_checkpoint_count(&self, citizen: AccountId) -> u32 {
+            self.checkpoint_counts.get(citizen).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn zone_count(&self) -> u32 {
+            self.zone_count
+        }
+
+        #[ink(message)]
+        pub fn get_governance(&self) -> AccountId {
+            self.governance
+        }
+
+        #[ink(message)]
+        pub fn get_citizen_registry(&self) -> Option<AccountId> {
+            self.citizen_registry
+        }
+
+        #[ink(message)]
+        pub fn set_citizen_registry(&mut self, citizen_registry: Option<AccountId>) -> Result<()> {
+            self.ensure_governance()?;
+            self.citizen_registry = citizen_registry;
+            self.env().emit_event(CitizenRegistryUpdated { citizen_registry });
+            Ok(())
+        }
+
+        fn ensure_governance(&self) -> Result<()> {
+            if self.env().caller() != self.governance {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+
+        /// Requires `caller` to be the registrar of `zone_id` or governance.
+        fn ensure_registrar(&self, zone_id: ZoneId, caller: AccountId) -> Result<()> {
+            let registrar = self.zones.get(zone_id).ok_or(Error::ZoneNotFound)?.registrar;
+            if caller != registrar && caller != self.governance {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+
+        fn is_citizen(&self, account: AccountId) -> bool {
+            match self.citizen_registry {
+                Some(registry) => {
+                    let registry: contract_ref!(CitizenRegistry) = registry.into();
+                    registry.is_citizen(account)
+                }
+                None => true,
+            }
+        }
+
+        /// Records the residency `citizen` holds from this block on. Moves
+        /// within one block leave a single checkpoint.
+        fn write_checkpoint(&mut self, citizen: AccountId, zone: Option<ZoneId>) {
+            let block = self.env().block_number();
+            let count = self.checkpoint_counts.get(citizen).unwrap_or(0);
+            let merged = count > 0
+                && self
+                    .checkpoints
+                    .get((citizen, count - 1))
+                    .is_some_and(|last| last.block == block);
+            let index = if merged { count - 1 } else { count };
+
+            self.checkpoints.insert((citizen, index), &ResidencyCheckpoint { block, zone });
+            self.checkpoint_counts.insert(citizen, &(index + 1));
+        }
+
+        fn update_population(&mut self, zone_id: ZoneId, update: impl FnOnce(u32) -> u32) {
+            if let Some(mut zone) = self.zones.get(zone_id) {
+                zone.population = update(zone.population);
+                self.zones.insert(zone_id, &zone);
+            }
+        }
+    }
+
+    impl ZoneDirectory for ZoneRegistry {
+        #[ink(message)]
+        fn zone_of(&self, account: AccountId) -> Option<ZoneId> {
+            self.residency.get(account)
+        }
+
+        #[ink(message)]
+        fn zone_of_at(&self, account: AccountId, block: BlockNumber) -> Option<ZoneId> {
+            // Binary search for the last checkpoint strictly before `block`
+            let (mut low, mut high) = (0, self.checkpoint_counts.get(account).unwrap_or(0));
+            while low < high {
+                let mid = low + (high - low) / 2;
+                match self.checkpoints.get((account, mid)) {
+                    Some(checkpoint) if checkpoint.block < block => low = mid + 1,
+                    _ => high = mid,
+                }
+            }
+
+            if low == 0 {
+                return None;
+            }
+            self.checkpoints.get((account, low - 1)).and_then(|checkpoint| checkpoint.zone)
+        }
+
+        #[ink(message)]
+        fn population(&self, zone: ZoneId) -> Option<u32> {
+            self.zones.get(zone).map(|zone| zone.population)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        /// Registry governed by Alice, with a zone kept by Bob and one kept
+        /// by Charlie.
+        fn registry() -> ZoneRegistry {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut registry = ZoneRegistry::new(accounts.alice, None);
+            assert_eq!(registry.create_zone(String::from("Downtown"), accounts.bob), Ok(0));
+            assert_eq!(registry.create_zone(String::from("Harbour"), accounts.charlie), Ok(1));
+            registry
+        }
+
+        #[ink::test]
+        fn only_governance_creates_zones() {
+            let accounts = accounts();
+            let mut registry = registry();
+
+            set_caller(accounts.bob);
+            assert_eq!(registry.create_zone(String::from("Uptown"), accounts.bob), Err(Error::Unauthorized));
+            assert_eq!(registry.set_registrar(0, accounts.django), Err(Error::Unauthorized));
+
+            set_caller(accounts.alice);
+            assert_eq!(registry.set_registrar(2, accounts.django), Err(Error::ZoneNotFound));
+            assert_eq!(registry.set_registrar(0, accounts.django), Ok(()));
+            assert_eq!(registry.get_zone(0).unwrap().registrar, accounts.django);
+            assert_eq!(registry.zone_count(), 2);
+        }
+
+        #[ink::test]
+        fn registrars_keep_the_residents_of_their_zone() {
+            let accounts = accounts();
+            let mut registry = registry();
+
+            set_caller(accounts.charlie);
+            assert_eq!(registry.set_residency(accounts.eve, Some(0)), Err(Error::Unauthorized));
+            assert_eq!(registry.set_residency(accounts.eve, Some(2)), Err(Error::ZoneNotFound));
+            set_caller(accounts.bob);
+            assert_eq!(registry.set_residency(accounts.eve, Some(0)), Ok(()));
+            assert_eq!(registry.set_residency(accounts.frank, Some(0)), Ok(()));
+            assert_eq!((registry.zone_of(accounts.eve), registry.population(0)), (Some(0), Some(2)));
+
+            // Leaving takes the registrar of the zone left
+            set_caller(accounts.charlie);
+            assert_eq!(registry.set_residency(accounts.frank, None), Err(Error::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(registry.set_residency(accounts.frank, None), Ok(()));
+            assert_eq!((registry.zone_of(accounts.frank), registry.population(0)), (None, Some(1)));
+            assert_eq!(registry.population(2), None);
+        }
+
+        #[ink::test]
+        fn moves_need_both_registrars() {
+            let accounts = accounts();
+            let mut registry = registry();
+            set_caller(accounts.bob);
+            assert_eq!(registry.set_residency(accounts.django, Some(0)), Ok(()));
+
+            // Charlie cannot take Bob's resident, nor can Bob place them in
+            // Charlie's zone
+            set_caller(accounts.charlie);
+            assert_eq!(registry.set_residency(accounts.django, Some(1)), Err(Error::Unauthorized));
+            assert_eq!(registry.set_residency(accounts.django, None), Err(Error::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(registry.set_residency(accounts.django, Some(1)), Err(Error::Unauthorized));
+
+            assert_eq!(registry.set_residency(accounts.django, None), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(registry.set_residency(accounts.django, Some(1)), Ok(()));
+            assert_eq!(registry.zone_of(accounts.django), Some(1));
+            assert_eq!(registry.population(0), Some(0));
+            assert_eq!(registry.population(1), Some(1));
+        }
+
+        #[ink::test]
+        fn governance_moves_residents_at_once() {
+            let accounts = accounts();
+            let mut registry = registry();
+            set_caller(accounts.bob);
+            assert_eq!(registry.set_residency(accounts.django, Some(0)), Ok(()));
+
+            set_caller(accounts.alice);
+            assert_eq!(registry.set_residency(accounts.django, Some(1)), Ok(()));
+            assert_eq!(registry.zone_of(accounts.django), Some(1));
+            assert_eq!(registry.set_residency(accounts.django, Some(2)), Err(Error::ZoneNotFound));
+        }
+
+        #[ink::test]
+        fn residency_is_read_as_it_stood_before_a_block() {
+            let accounts = accounts();
+            let mut registry = registry();
+            set_caller(accounts.alice);
+            assert_eq!(registry.set_residency(accounts.django, Some(0)), Ok(()));
+            // Moves within one block share a checkpoint
+            assert_eq!(registry.set_residency(accounts.django, Some(1)), Ok(()));
+            assert_eq!(registry.get_checkpoint_count(accounts.django), 1);
+
+            test::advance_block::<DefaultEnvironment>();
+            test::advance_block::<DefaultEnvironment>();
+            assert_eq!(registry.set_residency(accounts.django, None), Ok(()));
+            assert_eq!(registry.get_checkpoint(accounts.django, 1).unwrap().block, 2);
+
+            assert_eq!(registry.zone_of_at(accounts.django, 0), None);
+            assert_eq!(registry.zone_of_at(accounts.django, 1), Some(1));
+            assert_eq!(registry.zone_of_at(accounts.django, 2), Some(1));
+            assert_eq!(registry.zone_of_at(accounts.django, 3), None);
+            assert_eq!(registry.zone_of_at(accounts.eve, 3), None);
+        }
+    }
+}
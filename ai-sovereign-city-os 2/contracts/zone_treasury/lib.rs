@@ -0,0 +1,637 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod zone_treasury {
+    use city_common::agents::AgentStats;
+    use city_common::treasury::{TreasuryError, ZoneFunding};
+    use city_common::ZoneId;
+    use ink::contract_ref;
+    use ink::storage::Mapping;
+
+    /// Penalty and bonus rates are expressed in basis points of the base allocation.
+    pub const BASIS_POINTS: u128 = 10_000;
+
+    /// `bps` basis points of `amount`, saturating instead of overflowing.
+    fn share_of(amount: Balance, bps: u128) -> Balance {
+        // Split to keep `amount * bps` from overflowing
+        (amount / BASIS_POINTS)
+            .saturating_mul(bps)
+            .saturating_add(amount % BASIS_POINTS * bps / BASIS_POINTS)
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        Unauthorized,
+        InvalidParams,
+        ZoneNotFound,
+        PeriodNotEnded,
+        UsageNotReported,
+        UsageAlreadyReported,
+        PeriodSettled,
+        InsufficientFunds,
+        TransferFailed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Rates applied to every zone when a period is settled.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Params {
+        /// Share of the allocation withheld when the energy cap is exceeded.
+        pub energy_penalty_bps: u32,
+        /// Share of the allocation withheld when the bandwidth cap is exceeded.
+        pub bandwidth_penalty_bps: u32,
+        /// Bonus paid per unit of energy the zone's agents saved in the period.
+        pub bonus_per_energy_unit: Balance,
+        /// Upper bound on the bonus, as a share of the allocation.
+        pub max_bonus_bps: u32,
+    }
+
+    impl Params {
+        fn is_valid(&self) -> bool {
+            self.energy_penalty_bps as u128 + self.bandwidth_penalty_bps as u128 <= BASIS_POINTS
+        }
+
+        /// Part of `allocation` withheld for the caps a zone exceeded.
+        fn penalty(&self, allocation: Balance, over_energy: bool, over_bandwidth: bool) -> Balance {
+            let mut penalty_bps = 0;
+            if over_energy {
+                penalty_bps += self.energy_penalty_bps as u128;
+            }
+            if over_bandwidth {
+                penalty_bps += self.bandwidth_penalty_bps as u128;
+            }
+            share_of(allocation, penalty_bps)
+        }
+
+        /// Bonus on top of `allocation` for `energy_saved` units of energy.
+        fn bonus(&self, allocation: Balance, energy_saved: u128) -> Balance {
+            let max_bonus = share_of(allocation, self.max_bonus_bps as u128);
+            energy_saved.saturating_mul(self.bonus_per_energy_unit).min(max_bonus)
+        }
+    }
+
+    /// A zone's budget for each period and the caps it is held to.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ZoneBudget {
+        /// Account the zone's payouts go to.
+        pub recipient: AccountId,
+        pub allocation: Balance,
+        pub energy_cap: u128,
+        pub bandwidth_cap: u128,
+    }
+
+    /// A zone's budget from `from_period` on, until the next change.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct BudgetChange {
+        pub from_period: u64,
+        pub budget: ZoneBudget,
+    }
+
+    /// Settlement progress of a budgeted zone.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ZoneState {
+        /// Oldest period not settled yet.
+        pub next_period: u64,
+        /// Cumulative agent energy savings already rewarded.
+        pub energy_saved_rewarded: u128,
+        /// Budget change in force for `next_period`.
+        pub budget_change: u32,
+        /// Number of budget changes recorded for the zone.
+        pub budget_changes: u32,
+    }
+
+    /// Resource usage of a zone over one period, as reported by the meter.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Usage {
+        pub energy: u128,
+        pub bandwidth: u128,
+    }
+
+    #[ink(storage)]
+    pub struct ZoneTreasury {
+        /// Latest budget of each zone, in force from the period after it was set.
+        budgets: Mapping<ZoneId, ZoneBudget>,
+        budget_history: Mapping<(ZoneId, u32), BudgetChange>,
+        states: Mapping<ZoneId, ZoneState>,
+        usage: Mapping<(ZoneId, u64), Usage>, // (zone, period) -> usage
+        params: Params,
+        /// Start of period 0.
+        genesis: Timestamp,
+        period_length: Timestamp,
+        agent_registry: AccountId,
+        /// City treasury every payout is drawn from.
+        city_treasury: AccountId,
+        /// Oracle reporting zone energy and bandwidth usage.
+        meter: AccountId,
+        governance: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ZoneBudgetSet {
+        #[ink(topic)]
+        zone_id: ZoneId,
+        budget: Option<ZoneBudget>,
+    }
+
+    #[ink(event)]
+    pub struct UsageReported {
+        #[ink(topic)]
+        zone_id: ZoneId,
+        period: u64,
+        energy: u128,
+        bandwidth: u128,
+    }
+
+    #[ink(event)]
+    pub struct ZoneSettled {
+        #[ink(topic)]
+        zone_id: ZoneId,
+        period: u64,
+        allocation: Balance,
+        penalty: Balance,
+        bonus: Balance,
+    }
+
+    impl ZoneTreasury {
+        #[ink(constructor)]
+        pub fn new(
+            governance: AccountId,
+            city_treasury: AccountId,
+            agent_registry: AccountId,
+            meter: AccountId,
+            period_length: Timestamp,
+        ) -> Self {
+            assert!(period_length > 0, "invalid period length");
+            Self {
+                budgets: Mapping::default(),
+                budget_history: Mapping::default(),
+                states: Mapping::default(),
+                usage: Mapping::default(),
+                params: Params {
+                    energy_penalty_bps: 0,
+                    bandwidth_penalty_bps: 0,
+                    bonus_per_energy_unit: 0,
+                    max_bonus_bps: 0,
+                },
+                genesis: Self::env().block_timestamp(),
+                period_length,
+                agent_registry,
+                city_treasury,
+                meter,
+                governance,
+            }
+        }
+
+        /// Sets the budget and caps of a zone, or stops budgeting it with
+        /// `None`. A newly budgeted zone starts with the current period; a
+        /// change to a budgeted zone applies from the next period, so every
+        /// period keeps the budget it opened with.
+        #[ink(message)]
+        pub fn set_zone_budget(&mut self, zone_id: ZoneId, budget: Option<ZoneBudget>) -> Result<()> {
+            self.ensure_governance()?;
+            match &budget {
+                Some(budget) => {
+                    let current_period = self.current_period();
+                    let mut state = match self.states.get(zone_id) {
+                        Some(state) => state,
+                        None => {
+                            let registry: contract_ref!(AgentStats) = self.agent_registry.into();
+                            ZoneState {
+                                next_period: current_period,
+                                energy_saved_rewarded: registry.zone_energy_saved(zone_id),
+                                budget_change: 0,
+                                budget_changes: 0,
+                            }
+                        }
+                    };
+                    let from_period = if state.budget_changes == 0 { current_period } else { current_period + 1 };
+                    // A second change within a period replaces the first
+                    let replaces_last = state.budget_changes > 0
+                        && self.budget_change(zone_id, state.budget_changes - 1).from_period == from_period;
+                    if !replaces_last {
+                        state.budget_changes += 1;
+                    }
+                    self.budget_history.insert(
+                        (zone_id, state.budget_changes - 1),
+                        &BudgetChange {
+                            from_period,
+                            budget: budget.clone(),
+                        },
+                    );
+                    self.states.insert(zone_id, &state);
+                    self.budgets.insert(zone_id, budget);
+                }
+                None => {
+                    self.budgets.remove(zone_id);
+                    self.states.remove(zone_id);
+                }
+            }
+
+            self.env().emit_event(ZoneBudgetSet { zone_id, budget });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_params(&mut self, params: Params) -> Result<()> {
+            self.ensure_governance()?;
+            if !params.is_valid() {
+                return Err(Error::InvalidParams);
+            }
+            self.params = params;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_meter(&mut self, meter: AccountId) -> Result<()> {
+            self.ensure_governance()?;
+            self.meter = meter;
+            Ok(())
+        }
+
+        /// Records a zone's usage over a finished period. Only the meter may
+        /// report, once per zone and period, and only until the period is
+        /// settled.
+        #[ink(message)]
+        pub fn report_usage(&mut self, zone_id: ZoneId, period: u64, usage: Usage) -> Result<()> {
+            if self.env().caller() != self.meter {
+                return Err(Error::Unauthorized);
+            }
+            let state = self.states.get(zone_id).ok_or(Error::ZoneNotFound)?;
+            if period >= self.current_period() {
+                return Err(Error::PeriodNotEnded);
+            }
+            if period < state.next_period {
+                return Err(Error::PeriodSettled);
+            }
+            if self.usage.contains((zone_id, period)) {
+                return Err(Error::UsageAlreadyReported);
+            }
+
+            self.usage.insert((zone_id, period), &usage);
+
+            self.env().emit_event(UsageReported {
+                zone_id,
+                period,
+                energy: usage.energy,
+                bandwidth: usage.bandwidth,
+            });
+
+            Ok(())
+        }
+
+        /// Pays a zone for its oldest unsettled period, out of the city
+        /// treasury: the allocation the period opened with, less a penalty
+        /// per exceeded cap, plus a bonus for the energy its agents saved
+        /// since the last settlement. Anyone may settle. The meter has until
+        /// the end of the following period to report; a period left
+        /// unreported after that counts as over both caps.
+        #[ink(message)]
+        pub fn settle(&mut self, zone_id: ZoneId) -> Result<Balance> {
+            let mut state = self.states.get(zone_id).ok_or(Error::ZoneNotFound)?;
+            let period = state.next_period;
+            if period >= self.current_period() {
+                return Err(Error::PeriodNotEnded);
+            }
+            let budget = self.period_budget(zone_id, &mut state, period);
+            let (over_energy, over_bandwidth) = self.caps_exceeded(zone_id, &budget, period)?;
+
+            let penalty = self.params.penalty(budget.allocation, over_energy, over_bandwidth);
+
+            let registry: contract_ref!(AgentStats) = self.agent_registry.into();
+            let energy_saved = registry.zone_energy_saved(zone_id);
+            let bonus = self
+                .params
+                .bonus(budget.allocation, energy_saved.saturating_sub(state.energy_saved_rewarded));
+
+            let amount = (budget.allocation - penalty).saturating_add(bonus);
+
+            state.next_period += 1;
+            state.energy_saved_rewarded = energy_saved;
+            self.states.insert(zone_id, &state);
+            let mut treasury: contract_ref!(ZoneFunding) = self.city_treasury.into();
+            treasury
+                .draw_zone_budget(zone_id, period, budget.recipient, amount)
+                .map_err(|error| match error {
+                    TreasuryError::InsufficientFunds => Error::InsufficientFunds,
+                    _ => Error::TransferFailed,
+                })?;
+
+            self.env().emit_event(ZoneSettled {
+                zone_id,
+                period,
+                allocation: budget.allocation,
+                penalty,
+                bonus,
+            });
+
+            Ok(amount)
+        }
+
+        /// Index of the period running now.
+        #[ink(message)]
+        pub fn current_period(&self) -> u64 {
+            (self.env().block_timestamp() - self.genesis) / self.period_length
+        }
+
+        #[ink(message)]
+        pub fn get_zone_budget(&self, zone_id: ZoneId) -> Option<ZoneBudget> {
+            self.budgets.get(zone_id)
+        }
+
+        #[ink(message)]
+        pub fn get_budget_change(&self, zone_id: ZoneId, index: u32) -> Option<BudgetChange> {
+            self.budget_history.get((zone_id, index))
+        }
+
+        #[ink(message)]
+        pub fn get_zone_state(&self, zone_id: ZoneId) -> Option<ZoneState> {
+            self.states.get(zone_id)
+        }
+
+        #[ink(message)]
+        pub fn get_usage(&self, zone_id: ZoneId, period: u64) -> Option<Usage> {
+            self.usage.get((zone_id, period))
+        }
+
+        #[ink(message)]
+        pub fn get_params(&self) -> Params {
+            self.params.clone()
+        }
+
+        #[ink(message)]
+        pub fn get_governance(&self) -> AccountId {
+            self.governance
+        }
+
+        fn budget_change(&self, zone_id: ZoneId, index: u32) -> BudgetChange {
+            self.budget_history
+                .get((zone_id, index))
+                .expect("budget changes below the count are recorded")
+        }
+
+        /// Budget `period` opened with, moving `state` past the changes made
+        /// before it.
+        fn period_budget(&self, zone_id: ZoneId, state: &mut ZoneState, period: u64) -> ZoneBudget {
+            while state.budget_change + 1 < state.budget_changes
+                && self.budget_change(zone_id, state.budget_change + 1).from_period <= period
+            {
+                state.budget_change += 1;
+            }
+            self.budget_change(zone_id, state.budget_change).budget
+        }
+
+        /// Whether a zone went over its energy and bandwidth caps in a
+        /// finished period. Unreported periods count as over both once the
+        /// following period has ended too.
+        fn caps_exceeded(&self, zone_id: ZoneId, budget: &ZoneBudget, period: u64) -> Result<(bool, bool)> {
+            match self.usage.get((zone_id, period)) {
+                Some(usage) => Ok((usage.energy > budget.energy_cap, usage.bandwidth > budget.bandwidth_cap)),
+                None if period + 1 < self.current_period() => Ok((true, true)),
+                None => Err(Error::UsageNotReported),
+            }
+        }
+
+        fn ensure_governance(&self) -> Result<()> {
+            if self.env().caller() != self.governance {
+                return Err(Error::Unauthorized);
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{self, DefaultAccounts};
+        use ink::env::DefaultEnvironment;
+
+        const PERIOD_LENGTH: Timestamp = 100;
+
+        fn accounts() -> DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        /// Treasury governed by Alice, drawing from Frank's city treasury, with
+        /// Charlie's registry and Bob as the meter.
+        fn treasury() -> ZoneTreasury {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            ZoneTreasury::new(accounts.alice, accounts.frank, accounts.charlie, accounts.bob, PERIOD_LENGTH)
+        }
+
+        /// Budgets zone 0 for Eve. Written straight to storage, since
+        /// `set_zone_budget` reads the agent registry, which off-chain tests
+        /// cannot call.
+        fn budget_zone(treasury: &mut ZoneTreasury) {
+            let budget = zone_budget(1_000);
+            treasury.budgets.insert(0, &budget);
+            treasury.budget_history.insert((0, 0), &BudgetChange { from_period: 0, budget });
+            treasury.states.insert(
+                0,
+                &ZoneState {
+                    next_period: 0,
+                    energy_saved_rewarded: 0,
+                    budget_change: 0,
+                    budget_changes: 1,
+                },
+            );
+        }
+
+        fn zone_budget(allocation: Balance) -> ZoneBudget {
+            ZoneBudget {
+                recipient: accounts().eve,
+                allocation,
+                energy_cap: 50,
+                bandwidth_cap: 80,
+            }
+        }
+
+        fn params() -> Params {
+            Params {
+                energy_penalty_bps: 2_000,
+                bandwidth_penalty_bps: 1_000,
+                bonus_per_energy_unit: 3,
+                max_bonus_bps: 500,
+            }
+        }
+
+        #[ink::test]
+        fn each_exceeded_cap_withholds_its_share() {
+            let params = params();
+            assert_eq!(params.penalty(1_000, false, false), 0);
+            assert_eq!(params.penalty(1_000, true, false), 200);
+            assert_eq!(params.penalty(1_000, false, true), 100);
+            assert_eq!(params.penalty(1_000, true, true), 300);
+        }
+
+        #[ink::test]
+        fn bonuses_are_paid_per_unit_up_to_the_cap() {
+            let params = params();
+            assert_eq!(params.bonus(1_000, 0), 0);
+            assert_eq!(params.bonus(1_000, 10), 30);
+            // At most 5% of the allocation
+            assert_eq!(params.bonus(1_000, 20), 50);
+            assert_eq!(params.bonus(1_000, u128::MAX), 50);
+        }
+
+        #[ink::test]
+        fn rates_of_huge_allocations_do_not_overflow() {
+            let params = params();
+            assert_eq!(params.penalty(u128::MAX, true, true), u128::MAX / 10 * 3 + 1);
+            assert_eq!(params.bonus(u128::MAX, u128::MAX), u128::MAX / 20);
+
+            let unbounded_bonus = Params {
+                bonus_per_energy_unit: u128::MAX,
+                max_bonus_bps: u32::MAX,
+                ..params
+            };
+            assert_eq!(unbounded_bonus.bonus(u128::MAX, 1), u128::MAX);
+        }
+
+        #[ink::test]
+        fn only_governance_sets_valid_params() {
+            let accounts = accounts();
+            let mut treasury = treasury();
+
+            set_caller(accounts.bob);
+            assert_eq!(treasury.set_params(params()), Err(Error::Unauthorized));
+            assert_eq!(treasury.set_meter(accounts.bob), Err(Error::Unauthorized));
+            assert_eq!(treasury.set_zone_budget(0, None), Err(Error::Unauthorized));
+
+            set_caller(accounts.alice);
+            let penalties_over_allocation = Params {
+                energy_penalty_bps: 6_000,
+                bandwidth_penalty_bps: 5_000,
+                ..params()
+            };
+            assert_eq!(treasury.set_params(penalties_over_allocation), Err(Error::InvalidParams));
+            assert_eq!(treasury.set_params(params()), Ok(()));
+            assert_eq!(treasury.get_params(), params());
+        }
+
+        #[ink::test]
+        fn the_meter_reports_each_finished_period_once() {
+            let accounts = accounts();
+            let mut treasury = treasury();
+            let usage = Usage {
+                energy: 60,
+                bandwidth: 40,
+            };
+
+            set_caller(accounts.bob);
+            assert_eq!(treasury.report_usage(0, 0, usage.clone()), Err(Error::ZoneNotFound));
+            budget_zone(&mut treasury);
+            assert_eq!(treasury.report_usage(0, 0, usage.clone()), Err(Error::PeriodNotEnded));
+
+            test::set_block_timestamp::<DefaultEnvironment>(PERIOD_LENGTH);
+            set_caller(accounts.django);
+            assert_eq!(treasury.report_usage(0, 0, usage.clone()), Err(Error::Unauthorized));
+            set_caller(accounts.bob);
+            assert_eq!(treasury.report_usage(0, 0, usage.clone()), Ok(()));
+            assert_eq!(treasury.report_usage(0, 0, usage.clone()), Err(Error::UsageAlreadyReported));
+            assert_eq!(treasury.get_usage(0, 0), Some(usage));
+        }
+
+        #[ink::test]
+        fn zones_settle_finished_and_reported_periods() {
+            let mut treasury = treasury();
+            assert_eq!(treasury.settle(0), Err(Error::ZoneNotFound));
+
+            budget_zone(&mut treasury);
+            assert_eq!(treasury.settle(0), Err(Error::PeriodNotEnded));
+            test::set_block_timestamp::<DefaultEnvironment>(PERIOD_LENGTH);
+            assert_eq!(treasury.settle(0), Err(Error::UsageNotReported));
+        }
+
+        #[ink::test]
+        fn unreported_periods_count_as_over_both_caps_after_a_grace_period() {
+            let mut treasury = treasury();
+            budget_zone(&mut treasury);
+            let budget = treasury.get_zone_budget(0).unwrap();
+
+            test::set_block_timestamp::<DefaultEnvironment>(2 * PERIOD_LENGTH - 1);
+            assert_eq!(treasury.caps_exceeded(0, &budget, 0), Err(Error::UsageNotReported));
+            assert_eq!(treasury.settle(0), Err(Error::UsageNotReported));
+
+            test::set_block_timestamp::<DefaultEnvironment>(2 * PERIOD_LENGTH);
+            assert_eq!(treasury.caps_exceeded(0, &budget, 0), Ok((true, true)));
+            // Period 1 is still within its grace period
+            assert_eq!(treasury.caps_exceeded(0, &budget, 1), Err(Error::UsageNotReported));
+
+            let usage = Usage {
+                energy: 50,
+                bandwidth: 81,
+            };
+            set_caller(accounts().bob);
+            assert_eq!(treasury.report_usage(0, 1, usage), Ok(()));
+            assert_eq!(treasury.caps_exceeded(0, &budget, 1), Ok((false, true)));
+        }
+
+        #[ink::test]
+        fn settled_periods_take_no_reports() {
+            let mut treasury = treasury();
+            budget_zone(&mut treasury);
+            let mut state = treasury.get_zone_state(0).unwrap();
+            state.next_period = 1;
+            treasury.states.insert(0, &state);
+
+            test::set_block_timestamp::<DefaultEnvironment>(2 * PERIOD_LENGTH);
+            set_caller(accounts().bob);
+            let usage = Usage {
+                energy: 0,
+                bandwidth: 0,
+            };
+            assert_eq!(treasury.report_usage(0, 0, usage.clone()), Err(Error::PeriodSettled));
+            assert_eq!(treasury.report_usage(0, 1, usage), Ok(()));
+        }
+
+        #[ink::test]
+        fn periods_keep_the_budget_they_opened_with() {
+            let mut treasury = treasury();
+            budget_zone(&mut treasury);
+
+            // Two changes during period 1 leave it alone; the last one wins
+            test::set_block_timestamp::<DefaultEnvironment>(PERIOD_LENGTH);
+            assert_eq!(treasury.set_zone_budget(0, Some(zone_budget(2_000))), Ok(()));
+            assert_eq!(treasury.set_zone_budget(0, Some(zone_budget(3_000))), Ok(()));
+            test::set_block_timestamp::<DefaultEnvironment>(3 * PERIOD_LENGTH);
+            assert_eq!(treasury.set_zone_budget(0, Some(zone_budget(4_000))), Ok(()));
+            assert_eq!(treasury.get_zone_budget(0), Some(zone_budget(4_000)));
+            assert_eq!(treasury.get_budget_change(0, 1).unwrap().from_period, 2);
+            assert_eq!(treasury.get_zone_state(0).unwrap().budget_changes, 3);
+
+            let mut state = treasury.get_zone_state(0).unwrap();
+            let allocations = (0..5)
+                .map(|period| treasury.period_budget(0, &mut state, period).allocation)
+                .collect::<Vec<_>>();
+            assert_eq!(allocations, vec![1_000, 1_000, 3_000, 3_000, 4_000]);
+            assert_eq!(state.budget_change, 2);
+        }
+    }
+}